@@ -1,42 +1,180 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use crate::enums::ai_phase::AiPhase;
+use crate::enums::ai_provider_error::AiProviderError;
 use crate::errors::{AicedError, AicedResult};
+use crate::helpers::analysis_tools::REPORT_CHANGE_TOOL;
+use crate::helpers::token_estimator;
+use crate::config::constants::{EXACT_TOKEN_COUNT_THRESHOLD, MAX_TOOL_ROUNDS};
+use crate::prompts::continuation_prompt::CONTINUATION_PROMPT;
+use crate::services::analysis_parser::CHANGE_MARKER;
+use crate::services::usage_tracker::UsageTracker;
+use crate::structs::ai::chat_message::ChatMessage;
+use crate::structs::ai::serving_provider::ServingProvider;
+use crate::structs::ai::tool_call::ToolCall;
+use crate::structs::ai::tool_definition::ToolDefinition;
+use crate::structs::ai::tool_result::ToolResult;
+use crate::structs::conversation_progress::ConversationProgress;
+use crate::structs::stream_progress::StreamProgress;
 use crate::structs::stream_result::StreamResult;
+use crate::structs::usage::token_usage::TokenUsage;
 use crate::traits::ai_provider::AiProvider;
 use futures::StreamExt;
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
 
 pub struct AicedAdapter {
-    ai_provider: Arc<dyn AiProvider>
+    ai_provider: Arc<dyn AiProvider>,
+    phase_providers: HashMap<AiPhase, Arc<dyn AiProvider>>,
+    usage_tracker: Arc<UsageTracker>,
+    repository: String,
+    cancellation: CancellationToken,
+    progress: watch::Sender<Option<StreamProgress>>,
 }
 
 impl AicedAdapter {
 
-    pub fn new(ai_provider: Arc<dyn AiProvider>) -> Self {
-        Self { ai_provider }
+    pub fn new(ai_provider: Arc<dyn AiProvider>, usage_tracker: Arc<UsageTracker>, repository: String, cancellation: CancellationToken) -> Self {
+        let (progress, _) = watch::channel(None);
+        Self { ai_provider, phase_providers: HashMap::new(), usage_tracker, repository, cancellation, progress }
     }
 
-    pub async fn stream_llm_chat(&self, user_prompt: String, system_prompt: String) -> AicedResult<StreamResult> {
+    /// Progress of the request currently streaming; `None` until its first item arrives.
+    pub fn subscribe_progress(&self) -> watch::Receiver<Option<StreamProgress>> {
+        self.progress.subscribe()
+    }
+
+    pub fn set_phase_provider(&mut self, phase: AiPhase, ai_provider: Arc<dyn AiProvider>) {
+        self.phase_providers.insert(phase, ai_provider);
+    }
+
+    fn provider_for(&self, phase: AiPhase) -> &Arc<dyn AiProvider> {
+        self.phase_providers.get(&phase).unwrap_or(&self.ai_provider)
+    }
+
+    pub async fn stream_llm_chat(&self, phase: AiPhase, user_prompt: String, system_prompt: String) -> AicedResult<StreamResult> {
+        let result = self.stream_conversation(phase, vec![ChatMessage::system(system_prompt).cached(), ChatMessage::user(user_prompt)]).await?;
+        if result.cancelled {
+            return Err(AicedError::cancelled(&format!("{} request", phase)));
+        }
+        Ok(result)
+    }
+
+    pub async fn stream_conversation(&self, phase: AiPhase, messages: Vec<ChatMessage>) -> AicedResult<StreamResult> {
+        let mut conversation = self.start_conversation();
+        self.stream_messages(phase, messages, None, &mut conversation).await
+    }
+
+    /// Clears the published progress; the requests of the conversation then add up in it.
+    fn start_conversation(&self) -> ConversationProgress {
+        self.progress.send_replace(None);
+        ConversationProgress::start()
+    }
+
+    /// Streams one request of `conversation`. Cancellation drops the response stream, which
+    /// aborts the HTTP request, and returns what was received so far with `cancelled` set.
+    async fn stream_messages(&self, phase: AiPhase, messages: Vec<ChatMessage>, tools: Option<Vec<ToolDefinition>>, conversation: &mut ConversationProgress) -> AicedResult<StreamResult> {
         let mut full_content = String::new();
         let mut input_tokens = 0u32;
         let mut output_tokens = 0u32;
+        let mut stop_reason = None;
+        let mut thinking = String::new();
+        let mut cache_creation_tokens = 0u32;
+        let mut cache_read_tokens = 0u32;
+        let mut tool_calls = Vec::new();
+        let mut cancelled = false;
+        let mut received_chars = 0usize;
+        let mut scanned = 0usize;
+        let mut changes_seen = 0usize;
+        let mut last_progress: Option<StreamProgress> = None;
+
+        if self.cancellation.is_cancelled() {
+            return Err(AicedError::cancelled(&format!("{} request", phase)));
+        }
 
-        let mut stream = match self.ai_provider.stream_chat(system_prompt, vec![user_prompt]).await {
+        self.usage_tracker.check_budget()?;
+        let provider = self.provider_for(phase);
+        // Items that name their provider override this, e.g. after a fallback switched.
+        let mut served_by = ServingProvider::of(provider.as_ref());
+
+        let stream = tokio::select! {
+            stream = async {
+                match tools {
+                    Some(tools) => provider.stream_chat_with_tools(messages, tools).await,
+                    None => provider.stream_chat(messages).await,
+                }
+            } => stream,
+            _ = self.cancellation.cancelled() => return Err(AicedError::cancelled(&format!("{} request", phase))),
+        };
+
+        let mut stream = match stream {
             Ok(stream) => stream,
-            Err(e) => {
-                return Err(AicedError::system_error(
-                    "analysis Error",
-                    &format!("Failed to connect to {} server", e)
-                ).into());
-            },
+            Err(e) => return Err(Self::provider_error(phase, &e)),
         };
 
         let mut item_count = 0;
-        while let Some(result) = stream.next().await {
+        loop {
+            let next = tokio::select! {
+                biased;
+                _ = self.cancellation.cancelled() => {
+                    log::warn!("🛑 {} request cancelled after {} stream items", phase, item_count);
+                    cancelled = true;
+                    break;
+                }
+                next = stream.next() => next,
+            };
+
+            let Some(result) = next else {
+                break;
+            };
             item_count += 1;
 
             match result {
                 Ok(item) => {
+                    if let Some(serving) = &item.served_by {
+                        if *serving != served_by {
+                            served_by = serving.clone();
+                        }
+                    }
+
+                    if item.is_restart {
+                        log::warn!("🔁 Provider restarted the {} request, discarding partial output", phase);
+                        // The marker names the provider that failed, which the usage so far goes to.
+                        self.record_usage(&served_by, phase, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens);
+                        conversation.finish_request(last_progress.take().map(|progress| StreamProgress {
+                            changes_seen: progress.changes_seen - changes_seen,
+                            ..progress
+                        }));
+                        input_tokens = 0;
+                        output_tokens = 0;
+                        cache_creation_tokens = 0;
+                        cache_read_tokens = 0;
+                        full_content.clear();
+                        thinking.clear();
+                        tool_calls.clear();
+                        received_chars = 0;
+                        scanned = 0;
+                        changes_seen = 0;
+                        continue;
+                    }
+
                     if !item.content.is_empty() {
                         full_content.push_str(&item.content);
+                        received_chars += item.content.chars().count();
+                        changes_seen += Self::count_change_markers(&full_content, &mut scanned);
+                    }
+
+                    if !item.thinking.is_empty() {
+                        thinking.push_str(&item.thinking);
+                        received_chars += item.thinking.chars().count();
+                    }
+
+                    if let Some(tool_call) = item.tool_call {
+                        if tool_call.name == REPORT_CHANGE_TOOL {
+                            changes_seen += 1;
+                        }
+                        received_chars += tool_call.input.to_string().chars().count();
+                        tool_calls.push(tool_call);
                     }
 
                     match item.input_tokens {
@@ -53,20 +191,362 @@ impl AicedAdapter {
                         None => {},
                     }
 
+                    cache_creation_tokens += item.cache_creation_tokens.unwrap_or(0);
+                    cache_read_tokens += item.cache_read_tokens.unwrap_or(0);
+
+                    if item.stop_reason.is_some() {
+                        stop_reason = item.stop_reason;
+                    }
+
+                    // The provider reports output tokens only once the response ends.
+                    let streamed_tokens = match stop_reason {
+                        Some(_) => output_tokens,
+                        None => output_tokens.max(token_estimator::estimate_tokens_from_chars(received_chars)),
+                    };
+                    let usage = TokenUsage {
+                        requests: 1,
+                        input_tokens: input_tokens as u64,
+                        output_tokens: streamed_tokens as u64,
+                        cache_creation_tokens: cache_creation_tokens as u64,
+                        cache_read_tokens: cache_read_tokens as u64,
+                        cost: 0.0,
+                    };
+                    let progress = conversation.with(StreamProgress {
+                        phase,
+                        input_tokens: input_tokens + cache_creation_tokens + cache_read_tokens,
+                        output_tokens: streamed_tokens,
+                        elapsed: conversation.started().elapsed(),
+                        estimated_cost: self.usage_tracker.estimate_cost(&served_by.model, &usage),
+                        changes_seen,
+                    });
+                    last_progress = Some(progress.clone());
+                    self.progress.send_replace(Some(progress));
+
                     if item.is_complete {
                         break;
                     }
                 }
                 Err(e) => {
                     log::info!("Stream error on item #{}: {}", item_count, e);
-                    return Err(AicedError::system_error(
-                        "analysis Error",
-                        &format!("Failed to connect to {} server", "analyze")
-                    ).into());
+                    conversation.finish_request(last_progress);
+                    self.record_usage(&served_by, phase, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens);
+                    return Err(Self::provider_error(phase, &e));
                 },
             }
         }
 
-        Ok(StreamResult { content: full_content, input_tokens, output_tokens })
+        conversation.finish_request(last_progress);
+        self.record_usage(&served_by, phase, input_tokens, output_tokens, cache_creation_tokens, cache_read_tokens);
+
+        Ok(StreamResult {
+            content: full_content,
+            input_tokens,
+            output_tokens,
+            stop_reason,
+            thinking,
+            cache_creation_tokens,
+            cache_read_tokens,
+            tool_calls,
+            cancelled,
+        })
+    }
+
+    /// Like `stream_llm_chat`, but when the response is cut off by the output token limit
+    /// the conversation is continued, up to `max_continuations` times. Each partial response
+    /// is trimmed after its last line equal to `block_end` so that no half-written block
+    /// ends up in the stitched result.
+    pub async fn stream_llm_chat_with_continuation(&self, phase: AiPhase, user_prompt: String, system_prompt: String, block_end: &str, max_continuations: u32) -> AicedResult<StreamResult> {
+        // Continuations re-send the whole prompt, so it is marked for caching as well.
+        let mut messages = vec![ChatMessage::system(system_prompt).cached(), ChatMessage::user(user_prompt).cached()];
+        let mut conversation = self.start_conversation();
+        let mut result = self.stream_messages(phase, messages.clone(), None, &mut conversation).await?;
+        let mut continuations = 0;
+
+        while result.is_truncated() && !result.cancelled {
+            if continuations >= max_continuations {
+                log::warn!("⚠️ {} response still truncated after {} continuations, keeping complete blocks only", phase, continuations);
+                result.content = Self::trim_to_block_end(&result.content, block_end).to_string();
+                break;
+            }
+
+            continuations += 1;
+            log::info!("✂️ {} response hit the output token limit, requesting continuation {}/{}", phase, continuations, max_continuations);
+
+            let stitched = Self::trim_to_block_end(&result.content, block_end).to_string();
+            messages.truncate(2);
+            messages.push(ChatMessage::assistant(stitched.trim_end()));
+            messages.push(ChatMessage::user(CONTINUATION_PROMPT));
+
+            let continuation = self.stream_messages(phase, messages.clone(), None, &mut conversation).await?;
+            let content = format!("{}\n{}", stitched.trim_end(), continuation.content.trim_start());
+            result = Self::combine_results(result, continuation, content);
+        }
+
+        Ok(result)
+    }
+
+    /// Offers `tools` to the model and answers every call it makes until it stops calling
+    /// them. Each call is checked with `validate`; rejected calls are reported back as
+    /// errors so the model can send a corrected version. The result holds all tool calls
+    /// that passed validation, across rounds.
+    pub async fn stream_llm_chat_with_tools<F>(&self, phase: AiPhase, user_prompt: String, system_prompt: String, tools: Vec<ToolDefinition>, validate: F) -> AicedResult<StreamResult>
+    where
+        F: Fn(&ToolCall) -> Result<(), String>,
+    {
+        let mut messages = vec![ChatMessage::system(system_prompt).cached(), ChatMessage::user(user_prompt).cached()];
+        let mut result: Option<StreamResult> = None;
+        let mut rounds = 0;
+        let mut conversation = self.start_conversation();
+
+        loop {
+            let mut round = self.stream_messages(phase, messages.clone(), Some(tools.clone()), &mut conversation).await?;
+
+            // A response cut off by the output token limit is resumed the same way as a
+            // tool round, as long as at least one call made it through.
+            let wants_more = (round.stop_reason.as_deref() == Some("tool_use") || round.is_truncated())
+                && !round.tool_calls.is_empty()
+                && !round.cancelled;
+
+            let mut tool_results = Vec::new();
+            let mut accepted = Vec::new();
+            for tool_call in &round.tool_calls {
+                let (content, is_error) = match validate(tool_call) {
+                    Ok(()) => {
+                        accepted.push(tool_call.clone());
+                        ("Recorded.".to_string(), false)
+                    }
+                    Err(e) => {
+                        log::warn!("⚠️ Rejected {} call: {}", tool_call.name, e);
+                        (e, true)
+                    }
+                };
+                tool_results.push(ToolResult { tool_call_id: tool_call.id.clone(), content, is_error });
+            }
+
+            messages.push(ChatMessage::assistant_tool_calls(round.content.clone(), round.tool_calls.clone()));
+            messages.push(ChatMessage::tool_results(tool_results));
+
+            round.tool_calls = accepted;
+            result = Some(match result {
+                Some(previous) => {
+                    let content = format!("{}{}", previous.content, round.content);
+                    Self::combine_results(previous, round, content)
+                }
+                None => round,
+            });
+
+            if !wants_more {
+                break;
+            }
+
+            rounds += 1;
+            if rounds >= MAX_TOOL_ROUNDS {
+                log::warn!("⚠️ {} still calling tools after {} rounds, keeping the calls received so far", phase, rounds);
+                break;
+            }
+        }
+
+        // The loop runs at least once, so a result is always present.
+        result.ok_or_else(|| AicedError::system_error("tool round", "no response received"))
+    }
+
+    fn combine_results(previous: StreamResult, next: StreamResult, content: String) -> StreamResult {
+        StreamResult {
+            content,
+            input_tokens: previous.input_tokens + next.input_tokens,
+            output_tokens: previous.output_tokens + next.output_tokens,
+            stop_reason: next.stop_reason,
+            thinking: [previous.thinking, next.thinking]
+                .into_iter()
+                .filter(|t| !t.is_empty())
+                .collect::<Vec<String>>()
+                .join("\n\n"),
+            cache_creation_tokens: previous.cache_creation_tokens + next.cache_creation_tokens,
+            cache_read_tokens: previous.cache_read_tokens + next.cache_read_tokens,
+            tool_calls: previous.tool_calls.into_iter().chain(next.tool_calls).collect(),
+            cancelled: next.cancelled,
+        }
+    }
+
+    /// Counts change headers on the lines completed since `scanned`, then moves `scanned` past them.
+    fn count_change_markers(content: &str, scanned: &mut usize) -> usize {
+        let complete = match content.rfind('\n') {
+            Some(last_newline) if last_newline >= *scanned => last_newline + 1,
+            _ => return 0,
+        };
+
+        let count = content[*scanned..complete]
+            .lines()
+            .filter(|line| line.trim_start().starts_with(CHANGE_MARKER))
+            .count();
+        *scanned = complete;
+        count
+    }
+
+    /// Cuts after the last complete block, or after the last complete line if no block finished.
+    pub fn trim_to_block_end<'a>(content: &'a str, block_end: &str) -> &'a str {
+        let mut offset = 0;
+        let mut last_block_end = None;
+        let mut last_line_end = 0;
+
+        for line in content.split_inclusive('\n') {
+            offset += line.len();
+            if line.trim() == block_end {
+                last_block_end = Some(offset);
+            }
+            if !line.ends_with('\n') {
+                break;
+            }
+            last_line_end = offset;
+        }
+
+        &content[..last_block_end.unwrap_or(last_line_end)]
+    }
+
+    /// Input token count for a prompt. The local estimate is used while it stays well below
+    /// `budget`; closer to it the provider's counting endpoint decides, falling back to the
+    /// estimate when the endpoint cannot be reached.
+    pub async fn count_tokens(&self, phase: AiPhase, user_prompt: String, system_prompt: String, budget: u32) -> u32 {
+        let messages = vec![ChatMessage::system(system_prompt), ChatMessage::user(user_prompt)];
+        let estimated = token_estimator::estimate_message_tokens(&messages);
+        if (estimated as f64) < budget as f64 * EXACT_TOKEN_COUNT_THRESHOLD {
+            return estimated;
+        }
+
+        match self.provider_for(phase).count_tokens(messages.clone()).await {
+            Ok(tokens) => tokens,
+            Err(e) => {
+                log::warn!("⚠️ Token counting unavailable ({}), using local estimate", e);
+                estimated
+            }
+        }
     }
-}
\ No newline at end of file
+
+    fn record_usage(&self, served_by: &ServingProvider, phase: AiPhase, input_tokens: u32, output_tokens: u32, cache_creation_tokens: u32, cache_read_tokens: u32) {
+        if served_by.billable {
+            let usage = TokenUsage {
+                requests: 1,
+                input_tokens: input_tokens as u64,
+                output_tokens: output_tokens as u64,
+                cache_creation_tokens: cache_creation_tokens as u64,
+                cache_read_tokens: cache_read_tokens as u64,
+                cost: 0.0,
+            };
+            self.usage_tracker.record(&self.repository, phase, served_by.kind, &served_by.model, usage);
+        }
+    }
+
+    fn provider_error(phase: AiPhase, error: &AiProviderError) -> AicedError {
+        AicedError::NetworkError {
+            operation: format!("{} request", phase),
+            url: None,
+            status_code: None,
+            reason: error.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use async_trait::async_trait;
+    use futures::Stream;
+    use tokio_util::sync::CancellationToken;
+    use super::AicedAdapter;
+    use crate::enums::ai_phase::AiPhase;
+    use crate::enums::ai_provider_error::AiProviderError;
+    use crate::enums::ai_provider_kind::AiProviderKind;
+    use crate::services::analysis_parser::END_CHANGE_MARKER;
+    use crate::services::usage_tracker::UsageTracker;
+    use crate::structs::ai::chat_message::ChatMessage;
+    use crate::structs::config::config::Config;
+    use crate::structs::stream_item::StreamItem;
+    use crate::traits::ai_provider::AiProvider;
+
+    /// Answers each request with the next scripted response and its stop reason.
+    struct ScriptedProvider {
+        responses: Mutex<VecDeque<(&'static str, &'static str)>>,
+        requests: Mutex<usize>,
+    }
+
+    #[async_trait]
+    impl AiProvider for ScriptedProvider {
+        fn model(&self) -> &str {
+            "scripted"
+        }
+
+        fn kind(&self) -> AiProviderKind {
+            AiProviderKind::Ollama
+        }
+
+        fn is_billable(&self) -> bool {
+            false
+        }
+
+        async fn stream_chat(&self, _messages: Vec<ChatMessage>)
+                             -> Result<Pin<Box<dyn Stream<Item = Result<StreamItem, AiProviderError>> + Send>>, AiProviderError> {
+            *self.requests.lock().unwrap() += 1;
+            let (content, stop_reason) = self.responses.lock().unwrap().pop_front().expect("no scripted response left");
+            let item = StreamItem::complete(content.to_string(), Some(stop_reason.to_string()), 0);
+            Ok(Box::pin(futures::stream::iter(vec![Ok(item)])))
+        }
+    }
+
+    fn adapter(responses: Vec<(&'static str, &'static str)>) -> (AicedAdapter, Arc<ScriptedProvider>) {
+        let provider = Arc::new(ScriptedProvider { responses: Mutex::new(responses.into()), requests: Mutex::new(0) });
+        let usage_tracker = Arc::new(UsageTracker::new(&Config::default()));
+        let adapter = AicedAdapter::new(provider.clone(), usage_tracker, "test".to_string(), CancellationToken::new());
+        (adapter, provider)
+    }
+
+    async fn continued(adapter: &AicedAdapter, max_continuations: u32) -> String {
+        adapter
+            .stream_llm_chat_with_continuation(AiPhase::Analysis, "user".to_string(), "system".to_string(), END_CHANGE_MARKER, max_continuations)
+            .await
+            .unwrap()
+            .content
+    }
+
+    #[test]
+    fn trims_after_the_last_complete_block() {
+        let content = "CHANGE: a\nEND_CHANGE\nCHANGE: b\n  END_CHANGE  \nCHANGE: c\nhalf";
+        assert_eq!(AicedAdapter::trim_to_block_end(content, END_CHANGE_MARKER), "CHANGE: a\nEND_CHANGE\nCHANGE: b\n  END_CHANGE  \n");
+    }
+
+    #[test]
+    fn trims_a_block_end_without_newline_and_ignores_it_mid_line() {
+        assert_eq!(AicedAdapter::trim_to_block_end("CHANGE: a\nEND_CHANGE", END_CHANGE_MARKER), "CHANGE: a\nEND_CHANGE");
+        assert_eq!(AicedAdapter::trim_to_block_end("CHANGE: a\nno END_CHANGE here\npartial", END_CHANGE_MARKER), "CHANGE: a\nno END_CHANGE here\n");
+    }
+
+    #[test]
+    fn trims_to_the_last_complete_line_without_any_block() {
+        assert_eq!(AicedAdapter::trim_to_block_end("summary\nCHANGE: a\nhal", END_CHANGE_MARKER), "summary\nCHANGE: a\n");
+        assert_eq!(AicedAdapter::trim_to_block_end("half", END_CHANGE_MARKER), "");
+    }
+
+    #[tokio::test]
+    async fn continuation_is_stitched_after_the_last_complete_block() {
+        let (adapter, provider) = adapter(vec![
+            ("CHANGE: a\nEND_CHANGE\nCHANGE: b\nhalf", "max_tokens"),
+            ("CHANGE: b\nEND_CHANGE\n", "end_turn"),
+        ]);
+
+        assert_eq!(continued(&adapter, 3).await, "CHANGE: a\nEND_CHANGE\nCHANGE: b\nEND_CHANGE\n");
+        assert_eq!(*provider.requests.lock().unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn hitting_max_continuations_keeps_complete_blocks_only() {
+        let (adapter, provider) = adapter(vec![
+            ("CHANGE: a\nEND_CHANGE\nCHANGE: b\nhalf", "max_tokens"),
+            ("CHANGE: b\nEND_CHANGE\nCHANGE: c\nhalf", "max_tokens"),
+            ("CHANGE: c\nEND_CHANGE\n", "end_turn"),
+        ]);
+
+        assert_eq!(continued(&adapter, 1).await, "CHANGE: a\nEND_CHANGE\nCHANGE: b\nEND_CHANGE\n");
+        assert_eq!(*provider.requests.lock().unwrap(), 2);
+    }
+}
@@ -1,7 +1,10 @@
 use std::path::Path;
 use std::fs;
 use std::rc::Rc;
+use crate::config::constants::{PROVIDER_BOUND_AI_SETTINGS, PROVIDER_SCOPED_AI_TABLES};
+use crate::enums::ai_provider_kind::AiProviderKind;
 use crate::errors::{AicedError, AicedResult};
+use crate::helpers::config_helper::ConfigHelper;
 use crate::structs::config::config::Config;
 
 pub struct ConfigManager;
@@ -14,13 +17,87 @@ impl ConfigManager {
         if config_locations.exists() {
             log::info!("📋 Loading config from: {}", config_locations.display());
             let content = fs::read_to_string(&config_locations)?;
-            let config: Config = toml::from_str(&content)?;
+            let mut table: toml::Table = toml::from_str(&content)?;
+            Self::inherit_global_sections(&mut table);
+            let config: Config = table.try_into()?;
             return Ok(Rc::new(config));
         }
 
         Ok(Rc::new(Config::default()))
     }
 
+    /// Repository tables inherit every setting they leave out from the global section of the
+    /// same name, so a `[repositories.ai]` table that only sets `model` keeps the global
+    /// provider, fallbacks and retry settings.
+    fn inherit_global_sections(table: &mut toml::Table) {
+        let global_ai = table.get("ai").and_then(toml::Value::as_table).cloned().unwrap_or_default();
+        let global_analysis = table.get("analysis").and_then(toml::Value::as_table).cloned().unwrap_or_default();
+        let global_prompts = table.get("prompts").and_then(toml::Value::as_table).cloned().unwrap_or_default();
+
+        if let Some(toml::Value::Table(ai)) = table.get_mut("ai") {
+            Self::inherit_phases(ai);
+        }
+
+        let Some(toml::Value::Array(repositories)) = table.get_mut("repositories") else {
+            return;
+        };
+        for repo in repositories.iter_mut().filter_map(toml::Value::as_table_mut) {
+            if let Some(toml::Value::Table(ai)) = repo.get_mut("ai") {
+                Self::inherit_ai(ai, &global_ai, PROVIDER_SCOPED_AI_TABLES);
+                Self::inherit_phases(ai);
+            }
+            if let Some(toml::Value::Table(analysis)) = repo.get_mut("analysis") {
+                ConfigHelper::merge_missing(analysis, &global_analysis);
+            }
+            if let Some(toml::Value::Table(prompts)) = repo.get_mut("prompts") {
+                Self::inherit_prompts(prompts, &global_prompts);
+            }
+        }
+    }
+
+    /// Like `ConfigHelper::merge_missing`, but a table that switches to another provider does
+    /// not inherit the settings that only make sense for the parent's provider, nor the
+    /// `scoped` tables.
+    fn inherit_ai(ai: &mut toml::Table, parent: &toml::Table, scoped: &[&str]) {
+        let provider = |table: &toml::Table| table.get("provider").and_then(toml::Value::as_str).map(str::to_string);
+        let parent_provider = provider(parent).unwrap_or_else(|| AiProviderKind::default().to_string());
+        let switches_provider = provider(ai).is_some_and(|own| own != parent_provider);
+
+        let mut inherited = parent.clone();
+        if switches_provider {
+            for key in PROVIDER_BOUND_AI_SETTINGS.iter().chain(scoped) {
+                inherited.remove(*key);
+            }
+        }
+        ConfigHelper::merge_missing(ai, &inherited);
+    }
+
+    /// Each `[ai.phases.<phase>]` table inherits the settings it leaves out from its `[ai]`
+    /// table, fallbacks included.
+    fn inherit_phases(ai: &mut toml::Table) {
+        let mut parent = ai.clone();
+        parent.remove("phases");
+
+        if let Some(toml::Value::Table(phases)) = ai.get_mut("phases") {
+            for (_, phase) in phases.iter_mut() {
+                if let toml::Value::Table(phase) = phase {
+                    Self::inherit_ai(phase, &parent, &[]);
+                }
+            }
+        }
+    }
+
+    /// `guidelines` and `guidelines_file` exclude each other, so a table that sets either one
+    /// inherits neither.
+    pub fn inherit_prompts(prompts: &mut toml::Table, parent: &toml::Table) {
+        let mut inherited = parent.clone();
+        if prompts.contains_key("guidelines") || prompts.contains_key("guidelines_file") {
+            inherited.remove("guidelines");
+            inherited.remove("guidelines_file");
+        }
+        ConfigHelper::merge_missing(prompts, &inherited);
+    }
+
     pub fn create_sample_multi_repo_config() -> AicedResult<()> {
         let sample_config = r#"# Aiced Multi-Repository Configuration
 
@@ -28,6 +105,94 @@ impl ConfigManager {
 # How often to scan repositories: "hourly", "daily", "weekly", "manual"
 scan_interval = "daily"
 
+# AI provider settings (provider: "anthropic", "openai" or "ollama")
+[ai]
+provider = "anthropic"
+model = "claude-sonnet-4-20250514"
+# base_url = "http://localhost:8000/v1"   # OpenAI-compatible gateways, vLLM, LM Studio, Ollama
+max_tokens = 64000
+thinking = true
+thinking_budget = 63999
+request_timeout_secs = 900
+api_key_env = "ANTHROPIC_API_KEY"
+# context_window = 200000
+# What to do when the prompt exceeds the context window: "refuse", "warn" or "split"
+oversize_policy = "split"
+# "text" (line protocol) or "tools" (structured tool calls, Anthropic only; others fall back to text)
+# output_mode = "text"
+
+# Providers to try in order when the one above keeps failing with retryable errors
+# [[ai.fallbacks]]
+# provider = "openai"
+# model = "gpt-4o"
+
+# Optional per-phase routing: "file_filter", "analysis", "verification", "repair".
+# A phase table inherits the settings above that it leaves out.
+[ai.phases.file_filter]
+provider = "anthropic"
+model = "claude-3-5-haiku-20241022"
+max_tokens = 8192
+thinking = false
+
+[analysis]
+# How files are picked: "ai" (the model), "rules" (the settings below, no model call) or "rules+ai"
+file_selection = "ai"
+include_patterns = ["src/"]
+exclude_patterns = ["tests/", "target/", "node_modules/", "*.lock", "*.log"]
+languages = ["rust", "javascript", "typescript", "python"]
+# file_extensions = ["sql"]
+skip_tests = true
+# Files above max_file_size (B, KB, MB, GB) are skipped; beyond max_files the best ranked files are kept
+max_file_size = "1MB"
+max_files = 100
+# How files are split into analysis requests: "single", "by-directory", "by-module" or "smart"
+chunk_strategy = "smart"
+# max_batch_tokens = 60000
+max_concurrent_batches = 1
+# Categories to analyze (all when unset): bugs, security, performance, clean_code, architecture, duplicate_code
+# categories = ["security", "bugs"]
+# Only analyze files changed since a git ref, or since the last run with "last-run"
+# since = "last-run"
+# include_unchanged_neighbours = true
+
+# Cost accounting: prices per million tokens override the built-in table
+[pricing."claude-sonnet-4-20250514"]
+input_per_million = 3.0
+output_per_million = 15.0
+
+# Stop calling providers once a cap is reached
+[budget]
+max_cost_per_run = 5.0
+max_cost_per_day = 20.0
+
+# Record provider responses to cassettes, or replay them offline: "off", "record", "replay"
+[cassette]
+mode = "off"
+# dir = "/home/user/aiced/cassettes"
+
+# Proxy, private CA bundle and timeouts for the AI provider clients (notifications are not sent yet)
+[http]
+# proxy = "http://proxy.corp.example:3128"
+# no_proxy = "localhost,127.0.0.1,.corp.example"
+# ca_bundle = "/etc/ssl/certs/corp-ca.pem"
+connect_timeout_secs = 30
+read_timeout_secs = 300
+
+# Prompt templates (built-in when unset) and their variables. Templates may use
+# {{repository_name}}, {{language}}, {{focus_areas}}, {{categories}}, {{category_guide}} and {{guidelines}};
+# the user template also gets {{files}}. Inspect the result with `aiced prompt render`.
+[prompts]
+# analysis_template = "~/aiced/prompts/analysis.txt"
+# tools_template = "~/aiced/prompts/analysis_tools.txt"
+# user_template = "~/aiced/prompts/user.txt"
+language = "English"
+# guidelines_file = "~/aiced/guidelines.md"
+
+# Profiles selected with `aiced analyze --profile <name>`; the keys their sections set override
+# the global and per-repository ones
+# [profiles.release.prompts]
+# guidelines = "Only report issues that must be fixed before a release."
+
 # Repository definitions
 [[repositories]]
 name = "backend-api"
@@ -43,6 +208,13 @@ branch = "develop"
 auto_pull = false
 auto_pr = true
 
+# Per-repository AI settings; the ones left out are inherited from [ai]. Switching the
+# provider drops the global model, base_url, api_key_env, max_tokens, context_window,
+# fallbacks and phases, so this repository only talks to the local Ollama server
+[repositories.ai]
+provider = "ollama"
+model = "qwen2.5-coder"
+
 # Output Configuration
 [output]
 # Directory to store all analysis results
@@ -71,7 +243,33 @@ summary_report = true
             if !Path::new(&repo.path).exists() {
                 errors.push(format!("Repository '{}' path does not exist: {}", repo.name, repo.path));
             }
-           
+
+            if let Some(ai_config) = &repo.ai {
+                errors.extend(ai_config.validate().into_iter().map(|e| format!("Repository '{}': {}", repo.name, e)));
+            }
+
+            if let Some(analysis_config) = &repo.analysis {
+                errors.extend(analysis_config.validate().into_iter().map(|e| format!("Repository '{}': {}", repo.name, e)));
+            }
+
+            if let Some(prompt_config) = &repo.prompts {
+                errors.extend(prompt_config.validate().into_iter().map(|e| format!("Repository '{}': {}", repo.name, e)));
+            }
+        }
+
+        errors.extend(config.ai.validate());
+        errors.extend(config.analysis.validate());
+        errors.extend(config.budget.validate());
+        errors.extend(config.http.validate());
+        errors.extend(config.prompts.validate());
+
+        for (name, profile) in &config.profiles {
+            let mut profiled = (*config).clone();
+            let profile_errors = match profiled.merge_profile(profile) {
+                Ok(()) => profiled.analysis.validate().into_iter().chain(profiled.prompts.validate()).collect(),
+                Err(e) => vec![e.to_string()],
+            };
+            errors.extend(profile_errors.into_iter().map(|e| format!("Profile '{}': {}", name, e)));
         }
 
         let mut names = std::collections::HashSet::new();
@@ -84,8 +282,153 @@ summary_report = true
         if errors.is_empty() {
             Ok(())
         } else {
+            for error in &errors {
+                log::error!("❌ {}", error);
+            }
             Err(AicedError::config_error("Config Error", Some(""), Some("")))
         }
     }
     
 }
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigManager;
+
+    fn inherited(config: &str) -> toml::Table {
+        let mut table: toml::Table = toml::from_str(config).unwrap();
+        ConfigManager::inherit_global_sections(&mut table);
+        table
+    }
+
+    fn repository(table: &toml::Table) -> &toml::Table {
+        table["repositories"][0].as_table().unwrap()
+    }
+
+    const GLOBAL_AI: &str = r#"
+        [ai]
+        provider = "anthropic"
+        model = "claude-sonnet-4-20250514"
+        max_retries = 7
+
+        [[ai.fallbacks]]
+        provider = "openai"
+
+        [ai.phases.file_filter]
+        model = "claude-3-5-haiku-20241022"
+    "#;
+
+    #[test]
+    fn repository_on_the_same_provider_inherits_everything() {
+        let table = inherited(&format!("{}\n[[repositories]]\nname = \"a\"\n[repositories.ai]\nmodel = \"claude-opus-4-20250514\"\n", GLOBAL_AI));
+        let ai = repository(&table)["ai"].as_table().unwrap();
+
+        assert_eq!(ai["provider"].as_str(), Some("anthropic"));
+        assert_eq!(ai["model"].as_str(), Some("claude-opus-4-20250514"));
+        assert_eq!(ai["max_retries"].as_integer(), Some(7));
+        assert_eq!(ai["fallbacks"].as_array().map(Vec::len), Some(1));
+        assert_eq!(ai["phases"]["file_filter"]["model"].as_str(), Some("claude-3-5-haiku-20241022"));
+    }
+
+    #[test]
+    fn repository_switching_provider_drops_provider_bound_settings_fallbacks_and_phases() {
+        let table = inherited(&format!("{}\n[[repositories]]\nname = \"a\"\n[repositories.ai]\nprovider = \"ollama\"\n", GLOBAL_AI));
+        let ai = repository(&table)["ai"].as_table().unwrap();
+
+        assert_eq!(ai["provider"].as_str(), Some("ollama"));
+        assert_eq!(ai["max_retries"].as_integer(), Some(7));
+        assert!(!ai.contains_key("model"));
+        assert!(!ai.contains_key("fallbacks"));
+        assert!(!ai.contains_key("phases"));
+    }
+
+    #[test]
+    fn repository_phases_inherit_the_repository_ai_table() {
+        let config = r#"
+            [ai]
+            provider = "ollama"
+
+            [[repositories]]
+            name = "a"
+            [repositories.ai]
+            model = "qwen2.5-coder"
+            [repositories.ai.phases.analysis]
+            max_tokens = 2048
+        "#;
+        let table = inherited(config);
+        let phase = repository(&table)["ai"]["phases"]["analysis"].as_table().unwrap();
+
+        assert_eq!(phase["provider"].as_str(), Some("ollama"));
+        assert_eq!(phase["model"].as_str(), Some("qwen2.5-coder"));
+        assert_eq!(phase["max_tokens"].as_integer(), Some(2048));
+    }
+
+    #[test]
+    fn phases_inherit_fallbacks_but_not_provider_bound_settings_when_switching() {
+        let config = r#"
+            [ai]
+            provider = "anthropic"
+            model = "claude-sonnet-4-20250514"
+            [[ai.fallbacks]]
+            provider = "openai"
+
+            [ai.phases.analysis]
+            max_tokens = 1000
+            [ai.phases.file_filter]
+            provider = "ollama"
+        "#;
+        let table = inherited(config);
+        let phases = table["ai"]["phases"].as_table().unwrap();
+
+        assert_eq!(phases["analysis"]["model"].as_str(), Some("claude-sonnet-4-20250514"));
+        assert_eq!(phases["analysis"]["fallbacks"].as_array().map(Vec::len), Some(1));
+        assert!(!phases["file_filter"].as_table().unwrap().contains_key("model"));
+        assert_eq!(phases["file_filter"]["fallbacks"].as_array().map(Vec::len), Some(1));
+    }
+
+    #[test]
+    fn analysis_tables_merge_missing_keys() {
+        let config = r#"
+            [analysis]
+            max_files = 50
+            skip_tests = true
+
+            [[repositories]]
+            name = "a"
+            [repositories.analysis]
+            max_files = 10
+        "#;
+        let table = inherited(config);
+        let analysis = repository(&table)["analysis"].as_table().unwrap();
+
+        assert_eq!(analysis["max_files"].as_integer(), Some(10));
+        assert_eq!(analysis["skip_tests"].as_bool(), Some(true));
+    }
+
+    #[test]
+    fn guidelines_and_guidelines_file_are_inherited_together_or_not_at_all() {
+        let config = r#"
+            [prompts]
+            language = "German"
+            guidelines_file = "~/guidelines.md"
+
+            [[repositories]]
+            name = "a"
+            [repositories.prompts]
+            guidelines = "Be strict."
+
+            [[repositories]]
+            name = "b"
+            [repositories.prompts]
+            language = "French"
+        "#;
+        let table = inherited(config);
+        let first = table["repositories"][0]["prompts"].as_table().unwrap();
+        let second = table["repositories"][1]["prompts"].as_table().unwrap();
+
+        assert_eq!(first["language"].as_str(), Some("German"));
+        assert!(!first.contains_key("guidelines_file"));
+        assert_eq!(second["language"].as_str(), Some("French"));
+        assert_eq!(second["guidelines_file"].as_str(), Some("~/guidelines.md"));
+    }
+}
@@ -9,8 +9,75 @@ pub const DEFAULT_HISTORY_DAYS: u32 = 7;
 pub const MAX_SESSION_ID_LENGTH: usize = 64;
 pub const SERVER_SHUTDOWN_GRACE_PERIOD_MS: u64 = 100;
 pub const SESSION_CLEANUP_POLL_INTERVAL_MS: u64 = 500;
+pub const PROGRESS_LOG_INTERVAL_SECS: u64 = 15;
+/// How long a cassette recording keeps reading a body its consumer stopped reading early.
+pub const CASSETTE_DRAIN_TIMEOUT_SECS: u64 = 5;
+/// Conventional exit status for a process stopped by SIGINT (128 + 2).
+pub const EXIT_CODE_CANCELLED: i32 = 130;
 
 pub const ANTHROPIC_API_KEY_ENV: &str = "ANTHROPIC_API_KEY";
+pub const OPENAI_API_KEY_ENV: &str = "OPENAI_API_KEY";
+
+pub const DEFAULT_ANTHROPIC_BASE_URL: &str = "https://api.anthropic.com/v1";
+pub const DEFAULT_ANTHROPIC_MODEL: &str = "claude-sonnet-4-20250514";
+pub const DEFAULT_ANTHROPIC_MAX_TOKENS: u32 = 64000;
+pub const DEFAULT_ANTHROPIC_CONTEXT_WINDOW: u32 = 200_000;
+pub const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+pub const DEFAULT_OPENAI_MODEL: &str = "gpt-4o";
+pub const DEFAULT_OPENAI_MAX_TOKENS: u32 = 16384;
+pub const DEFAULT_OPENAI_CONTEXT_WINDOW: u32 = 128_000;
+pub const DEFAULT_OLLAMA_BASE_URL: &str = "http://localhost:11434";
+pub const DEFAULT_OLLAMA_MODEL: &str = "qwen2.5-coder";
+pub const DEFAULT_OLLAMA_MAX_TOKENS: u32 = 4096;
+pub const DEFAULT_OLLAMA_CONTEXT_WINDOW: u32 = 32_768;
+
+/// `[ai]` settings a table that switches to another provider does not inherit from its parent.
+pub const PROVIDER_BOUND_AI_SETTINGS: &[&str] = &["model", "base_url", "api_key_env", "max_tokens", "context_window"];
+/// Global `[ai]` tables a repository that switches to another provider does not inherit either,
+/// so that none of its requests go to the global providers.
+pub const PROVIDER_SCOPED_AI_TABLES: &[&str] = &["fallbacks", "phases"];
+pub const ESTIMATED_CHARS_PER_TOKEN: f64 = 3.0;
+/// Share of the input budget above which a prompt is counted by the provider instead of estimated.
+pub const EXACT_TOKEN_COUNT_THRESHOLD: f64 = 0.8;
+pub const MAX_TOOL_ROUNDS: u32 = 20;
+/// Categories a proposed change can be filed under.
+pub const ANALYSIS_CATEGORIES: &[&str] = &["BUGS", "SECURITY", "PERFORMANCE", "CLEAN_CODE", "ARCHITECTURE", "DUPLICATE_CODE"];
+/// `since` value that resolves to the commit analyzed by the last successful run.
+pub const SINCE_LAST_RUN: &str = "last-run";
+
+/// Files that mark the root of a module for the `by-module` chunk strategy.
+pub const MODULE_MANIFESTS: &[&str] = &[
+    "Cargo.toml", "package.json", "go.mod", "pyproject.toml", "setup.py", "pom.xml",
+    "build.gradle", "build.gradle.kts", "composer.json", "Gemfile", "mix.exs",
+];
+/// Lockfiles summarized into the project context as `name version` lines.
+pub const CONTEXT_LOCKFILES: &[&str] = &[
+    "Cargo.lock", "poetry.lock", "package-lock.json", "go.sum",
+];
+/// CI and container configuration sent as project context; names ending in `/` are directories.
+pub const CONTEXT_CONFIGS: &[&str] = &[
+    "Dockerfile", "docker-compose.yml", "docker-compose.yaml", "compose.yaml", ".gitlab-ci.yml",
+    "Jenkinsfile", ".github/workflows/", ".circleci/",
+];
+/// Ignore patterns applied before every ignore file, so a `.gitignore` can re-include them.
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
+    "*.jpg", "*.jpeg", "*.png", "*.gif", "*.bmp", "*.tiff", "*.tif",
+    "*.webp", "*.ico", "*.cur",
+    "*.raw", "*.cr2", "*.nef", "*.arw", "*.dng", "*.orf", "*.rw2",
+    "*.svg", "*.eps", "*.ai", "*.pdf",
+    "*.psd", "*.xcf", "*.sketch", "*.fig",
+    "Thumbs.db", ".DS_Store", "*.tmp",
+    "images/", "img/", "assets/images/", "static/images/",
+    "public/images/", "src/assets/", "assets/img/",
+];
+pub const GITIGNORE_FILE: &str = ".gitignore";
+/// Scanner-only ignore file; its rules take precedence over every git ignore rule.
+pub const AICEDIGNORE_FILE: &str = ".aicedignore";
+/// Directories that never hold project context worth sending.
+pub const CONTEXT_SKIP_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist", "build", ".git", ".venv", "venv"];
+pub const CONTEXT_SCAN_DEPTH: usize = 3;
+pub const MAX_CONTEXT_FILE_CHARS: usize = 8_000;
+pub const MAX_CONTEXT_CHARS: usize = 32_000;
 
 pub const SUPPORTED_FILE_EXTENSIONS: &[(&str, &str)] = &[
     ("rs", "rust"),
@@ -39,6 +106,22 @@ pub const SUPPORTED_FILE_EXTENSIONS: &[(&str, &str)] = &[
     ("md", "markdown"),
 ];
 
+/// Directories whose files count as tests in every language for `skip_tests`.
+pub const TEST_DIRS: &[&str] = &["tests", "test", "__tests__", "spec", "testdata"];
+/// File name patterns that mark a test file, per language from `SUPPORTED_FILE_EXTENSIONS`.
+pub const TEST_FILE_PATTERNS: &[(&str, &[&str])] = &[
+    ("rust", &["*_test.rs", "*_tests.rs", "tests.rs"]),
+    ("javascript", &["*.test.js", "*.spec.js", "*.test.jsx", "*.spec.jsx"]),
+    ("typescript", &["*.test.ts", "*.spec.ts", "*.test.tsx", "*.spec.tsx"]),
+    ("python", &["test_*.py", "*_test.py", "conftest.py"]),
+    ("java", &["*Test.java", "*Tests.java", "*IT.java"]),
+    ("go", &["*_test.go"]),
+    ("cpp", &["*_test.cc", "*_test.cpp", "*_unittest.cc"]),
+    ("c", &["test_*.c", "*_test.c"]),
+    ("php", &["*Test.php"]),
+    ("ruby", &["*_spec.rb", "*_test.rb"]),
+];
+
 pub const DEFAULT_FILE_TYPE: &str = "text";
 
 pub fn timeout_duration(minutes: u64) -> Duration {
@@ -0,0 +1,24 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Distinct kinds of provider calls made during a run, each of which can be routed
+/// to its own provider and model through `[ai.phases.<phase>]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AiPhase {
+    FileFilter,
+    Analysis,
+    Verification,
+    Repair,
+}
+
+impl fmt::Display for AiPhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AiPhase::FileFilter => write!(f, "file_filter"),
+            AiPhase::Analysis => write!(f, "analysis"),
+            AiPhase::Verification => write!(f, "verification"),
+            AiPhase::Repair => write!(f, "repair"),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::error::Error;
 use std::fmt;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub enum AiProviderError {
@@ -7,17 +8,57 @@ pub enum AiProviderError {
     NetworkError(String),
     SerializationError(String),
     AuthenticationError(String),
+    RateLimited {
+        message: String,
+        retry_after: Option<Duration>,
+    },
+    Overloaded(String),
+    Timeout(String),
+}
+
+impl AiProviderError {
+    /// Transient failures that are worth retrying with backoff.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            AiProviderError::NetworkError(_)
+                | AiProviderError::RateLimited { .. }
+                | AiProviderError::Overloaded(_)
+                | AiProviderError::Timeout(_)
+        )
+    }
+
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            AiProviderError::RateLimited { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+
+    pub fn from_reqwest(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            AiProviderError::Timeout(error.to_string())
+        } else {
+            AiProviderError::NetworkError(error.to_string())
+        }
+    }
 }
 
 impl fmt::Display for AiProviderError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            AiProviderError::ApiError(msg) => write!(f, "Anthropic API Error: {}", msg),
+            AiProviderError::ApiError(msg) => write!(f, "API Error: {}", msg),
             AiProviderError::NetworkError(msg) => write!(f, "Network Error: {}", msg),
             AiProviderError::SerializationError(msg) => write!(f, "Serialization Error: {}", msg),
             AiProviderError::AuthenticationError(msg) => write!(f, "Authentication Error: {}", msg),
+            AiProviderError::RateLimited { message, retry_after } => match retry_after {
+                Some(delay) => write!(f, "Rate Limited (retry after {}s): {}", delay.as_secs(), message),
+                None => write!(f, "Rate Limited: {}", message),
+            },
+            AiProviderError::Overloaded(msg) => write!(f, "Provider Overloaded: {}", msg),
+            AiProviderError::Timeout(msg) => write!(f, "Timeout: {}", msg),
         }
     }
 }
 
-impl Error for AiProviderError {}
\ No newline at end of file
+impl Error for AiProviderError {}
@@ -0,0 +1,74 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+use crate::config::constants::{
+    ANTHROPIC_API_KEY_ENV, DEFAULT_ANTHROPIC_BASE_URL, DEFAULT_ANTHROPIC_CONTEXT_WINDOW, DEFAULT_ANTHROPIC_MAX_TOKENS,
+    DEFAULT_ANTHROPIC_MODEL, DEFAULT_OLLAMA_BASE_URL, DEFAULT_OLLAMA_CONTEXT_WINDOW, DEFAULT_OLLAMA_MAX_TOKENS,
+    DEFAULT_OLLAMA_MODEL, DEFAULT_OPENAI_BASE_URL, DEFAULT_OPENAI_CONTEXT_WINDOW, DEFAULT_OPENAI_MAX_TOKENS,
+    DEFAULT_OPENAI_MODEL, OPENAI_API_KEY_ENV
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AiProviderKind {
+    #[default]
+    Anthropic,
+    OpenAi,
+    Ollama,
+}
+
+impl AiProviderKind {
+    pub fn default_model(&self) -> &'static str {
+        match self {
+            AiProviderKind::Anthropic => DEFAULT_ANTHROPIC_MODEL,
+            AiProviderKind::OpenAi => DEFAULT_OPENAI_MODEL,
+            AiProviderKind::Ollama => DEFAULT_OLLAMA_MODEL,
+        }
+    }
+
+    pub fn default_base_url(&self) -> &'static str {
+        match self {
+            AiProviderKind::Anthropic => DEFAULT_ANTHROPIC_BASE_URL,
+            AiProviderKind::OpenAi => DEFAULT_OPENAI_BASE_URL,
+            AiProviderKind::Ollama => DEFAULT_OLLAMA_BASE_URL,
+        }
+    }
+
+    pub fn default_max_tokens(&self) -> u32 {
+        match self {
+            AiProviderKind::Anthropic => DEFAULT_ANTHROPIC_MAX_TOKENS,
+            AiProviderKind::OpenAi => DEFAULT_OPENAI_MAX_TOKENS,
+            AiProviderKind::Ollama => DEFAULT_OLLAMA_MAX_TOKENS,
+        }
+    }
+
+    pub fn default_context_window(&self) -> u32 {
+        match self {
+            AiProviderKind::Anthropic => DEFAULT_ANTHROPIC_CONTEXT_WINDOW,
+            AiProviderKind::OpenAi => DEFAULT_OPENAI_CONTEXT_WINDOW,
+            AiProviderKind::Ollama => DEFAULT_OLLAMA_CONTEXT_WINDOW,
+        }
+    }
+
+    /// Whether the provider's client implements tool use, decided without building it.
+    pub fn supports_tools(&self) -> bool {
+        matches!(self, AiProviderKind::Anthropic)
+    }
+
+    pub fn default_api_key_env(&self) -> Option<&'static str> {
+        match self {
+            AiProviderKind::Anthropic => Some(ANTHROPIC_API_KEY_ENV),
+            AiProviderKind::OpenAi => Some(OPENAI_API_KEY_ENV),
+            AiProviderKind::Ollama => None,
+        }
+    }
+}
+
+impl fmt::Display for AiProviderKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AiProviderKind::Anthropic => write!(f, "anthropic"),
+            AiProviderKind::OpenAi => write!(f, "openai"),
+            AiProviderKind::Ollama => write!(f, "ollama"),
+        }
+    }
+}
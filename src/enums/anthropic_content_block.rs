@@ -0,0 +1,32 @@
+use serde::Serialize;
+use crate::structs::ai::anthropic::anthropic_cache_control::AnthropicCacheControl;
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicContentBlock {
+    Text {
+        text: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<AnthropicCacheControl>,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        is_error: bool,
+    },
+}
+
+impl AnthropicContentBlock {
+    pub fn text(text: String, cached: bool) -> Self {
+        AnthropicContentBlock::Text {
+            text,
+            cache_control: cached.then(AnthropicCacheControl::ephemeral),
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+/// How the selected files are split into analysis requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChunkStrategy {
+    /// Everything in one request; `oversize_policy` decides what happens when it does not fit.
+    Single,
+    /// One request per directory.
+    ByDirectory,
+    /// One request per module: the nearest directory with a package manifest.
+    ByModule,
+    /// Whole directories packed together into as few token-bounded requests as possible.
+    #[default]
+    Smart,
+}
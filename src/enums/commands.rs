@@ -1,5 +1,6 @@
 use clap::Subcommand;
 use crate::config::constants::{DEFAULT_DASHBOARD_PORT, DEFAULT_HISTORY_DAYS};
+use crate::enums::prompt_commands::PromptCommands;
 
 #[derive(Subcommand)]
 pub enum Commands {
@@ -11,6 +12,12 @@ pub enum Commands {
         tags: Vec<String>,
         #[clap(short, long)]
         profile: Option<String>,
+        /// Only analyze files changed since this git ref ("last-run" for the last successful run)
+        #[clap(long)]
+        since: Option<String>,
+        /// Only analyze these categories, e.g. "security,bugs"
+        #[clap(long, value_delimiter = ',')]
+        categories: Vec<String>,
     },
     List,
     Dashboard {
@@ -23,4 +30,16 @@ pub enum Commands {
         #[clap(short, long, default_value_t = DEFAULT_HISTORY_DAYS)]
         days: u32,
     },
+    /// List the files that pass the ignore rules, or explain which rule decides about a path
+    Scan {
+        #[clap(short, long)]
+        repo: String,
+        /// Show the ignore rule that decides whether this path is scanned
+        #[clap(long)]
+        explain: Option<String>,
+    },
+    Prompt {
+        #[clap(subcommand)]
+        action: PromptCommands,
+    },
 }
\ No newline at end of file
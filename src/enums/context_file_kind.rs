@@ -0,0 +1,19 @@
+use std::fmt;
+
+/// What a project context file is, as named in the prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContextFileKind {
+    Manifest,
+    LockfileSummary,
+    CiConfig,
+}
+
+impl fmt::Display for ContextFileKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContextFileKind::Manifest => write!(f, "manifest"),
+            ContextFileKind::LockfileSummary => write!(f, "lockfile summary"),
+            ContextFileKind::CiConfig => write!(f, "CI/container config"),
+        }
+    }
+}
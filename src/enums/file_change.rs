@@ -2,26 +2,36 @@ use crate::enums::line_change::LineChange;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action")]
 pub enum FileChange {
+    #[serde(rename = "modify_file")]
     ModifyFile {
         file_path: String,
         reason: String,
         severity: String,
         category: String,
         line_changes: Vec<LineChange>,
+        #[serde(default)]
+        rationale: Option<String>,
     },
+    #[serde(rename = "create_file")]
     CreateFile {
         file_path: String,
         reason: String,
         severity: String,
         category: String,
         content: String,
+        #[serde(default)]
+        rationale: Option<String>,
     },
+    #[serde(rename = "delete_file")]
     DeleteFile {
         file_path: String,
         reason: String,
         severity: String,
         category: String,
+        #[serde(default)]
+        rationale: Option<String>,
     },
 }
 
@@ -33,4 +43,20 @@ impl FileChange {
             FileChange::DeleteFile { file_path, .. } => file_path,
         }
     }
+
+    pub fn get_category(&self) -> &str {
+        match self {
+            FileChange::ModifyFile { category, .. }
+            | FileChange::CreateFile { category, .. }
+            | FileChange::DeleteFile { category, .. } => category,
+        }
+    }
+
+    pub fn set_rationale(&mut self, value: Option<String>) {
+        match self {
+            FileChange::ModifyFile { rationale, .. }
+            | FileChange::CreateFile { rationale, .. }
+            | FileChange::DeleteFile { rationale, .. } => *rationale = value,
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,37 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+/// How the scanner picks the files to analyze among those that pass the ignore rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum FileSelection {
+    /// Only the `[analysis]` include, exclude, language and test rules; no model call.
+    #[serde(rename = "rules")]
+    Rules,
+    /// The model picks the files, with the result cached until the file list changes.
+    #[default]
+    #[serde(rename = "ai")]
+    Ai,
+    /// The rules first, then the model picks among the remaining files.
+    #[serde(rename = "rules+ai")]
+    RulesAi,
+}
+
+impl FileSelection {
+    pub fn uses_rules(&self) -> bool {
+        matches!(self, FileSelection::Rules | FileSelection::RulesAi)
+    }
+
+    pub fn uses_ai(&self) -> bool {
+        matches!(self, FileSelection::Ai | FileSelection::RulesAi)
+    }
+}
+
+impl fmt::Display for FileSelection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FileSelection::Rules => write!(f, "rules"),
+            FileSelection::Ai => write!(f, "ai"),
+            FileSelection::RulesAi => write!(f, "rules+ai"),
+        }
+    }
+}
@@ -6,3 +6,16 @@ pub mod application_strategy;
 pub mod session_status;
 pub mod ai_provider_error;
 pub mod stream_event_data;
+pub mod ai_provider_kind;
+pub mod ai_phase;
+pub mod oversize_policy;
+
+pub mod cassette_mode;
+pub mod chat_role;
+pub mod output_mode;
+pub mod anthropic_content_block;
+pub mod chunk_strategy;
+pub mod prompt_commands;
+pub mod skip_reason;
+pub mod file_selection;
+pub mod context_file_kind;
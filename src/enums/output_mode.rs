@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// How the model reports its findings: the line-based text protocol, or structured
+/// tool calls for providers that support them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    #[default]
+    Text,
+    Tools,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// What to do when the analysis prompt does not fit into the model's context window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OversizePolicy {
+    Refuse,
+    Warn,
+    #[default]
+    Split,
+}
@@ -0,0 +1,18 @@
+use clap::Subcommand;
+
+#[derive(Subcommand)]
+pub enum PromptCommands {
+    /// Print the final analysis prompt for a repository
+    Render {
+        #[clap(short, long)]
+        repo: String,
+        #[clap(short, long)]
+        profile: Option<String>,
+        /// Render the prompt for these categories only, e.g. "security,bugs"
+        #[clap(long, value_delimiter = ',')]
+        categories: Vec<String>,
+        /// Print only the system prompt, without scanning the repository
+        #[clap(long)]
+        system_only: bool,
+    },
+}
@@ -0,0 +1,27 @@
+use std::fmt;
+use crate::helpers::config_helper::ConfigHelper;
+
+/// Why the scanner or the batching left a file out of the analysis.
+#[derive(Debug, Clone)]
+pub enum SkipReason {
+    TooLarge { size: u64, limit: u64 },
+    FileLimit { limit: usize },
+    TooManyTokens { tokens: u32, budget: u32 },
+    /// The selection rule that left the file out.
+    Rules(String),
+    Unreadable(String),
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SkipReason::TooLarge { size, limit } => {
+                write!(f, "too large ({} > max_file_size {})", ConfigHelper::format_size(*size), ConfigHelper::format_size(*limit))
+            }
+            SkipReason::FileLimit { limit } => write!(f, "over the max_files limit of {}", limit),
+            SkipReason::TooManyTokens { tokens, budget } => write!(f, "too large for one batch ({} tokens > {})", tokens, budget),
+            SkipReason::Rules(rule) => write!(f, "left out by {}", rule),
+            SkipReason::Unreadable(reason) => write!(f, "unreadable ({})", reason),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use serde::Deserialize;
 use crate::structs::ai::api_error::ApiError;
 use crate::structs::ai::anthropic::anthropic_message_delta::AnthropicMessageDelta;
+use crate::structs::ai::anthropic::anthropic_content_block_start::AnthropicContentBlockStart;
 use crate::structs::ai::anthropic::anthropic_content_delta::AnthropicContentDelta;
 use crate::structs::ai::anthropic::anthropic_finish_usage_info::AnthropicFinishUsageInfo;
 use crate::structs::ai::anthropic::anthropic_message_start_info::AnthropicMessageStartInfo;
@@ -13,15 +14,24 @@ pub enum StreamEventData {
         message: AnthropicMessageStartInfo,
     },
     #[serde(rename = "content_block_start")]
-    ContentBlockStart,
+    ContentBlockStart {
+        #[serde(default)]
+        index: usize,
+        content_block: AnthropicContentBlockStart,
+    },
     #[serde(rename = "content_block_stop")]
-    ContentBlockStop,
+    ContentBlockStop {
+        #[serde(default)]
+        index: usize,
+    },
     #[serde(rename = "message_stop")]
     MessageStop,
     #[serde(rename = "ping")]
     Ping,
     #[serde(rename = "content_block_delta")]
     ContentBlockDelta {
+        #[serde(default)]
+        index: usize,
         delta: AnthropicContentDelta,
     },
     #[serde(rename = "message_delta")]
@@ -75,6 +75,16 @@ pub enum AicedError {
         suggestion: String,
     },
 
+    BudgetExceeded {
+        scope: String,
+        limit: f64,
+        spent: f64,
+    },
+
+    Cancelled {
+        operation: String,
+    },
+
     MultipleErrors {
         errors: Vec<AicedError>,
         context: String,
@@ -114,6 +124,16 @@ impl AicedError {
         }
     }
 
+    pub fn cancelled(operation: &str) -> Self {
+        Self::Cancelled {
+            operation: operation.to_string(),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled { .. })
+    }
+
     pub fn system_error(operation: &str, reason: &str) -> Self {
         Self::SystemError {
             operation: operation.to_string(),
@@ -168,6 +188,8 @@ impl AicedError {
             Self::UserInputError { .. } => ErrorSeverity::Low,
             Self::FileValidationError { .. } => ErrorSeverity::Medium,
             Self::RepositoryError { .. } => ErrorSeverity::Medium,
+            Self::BudgetExceeded { .. } => ErrorSeverity::High,
+            Self::Cancelled { .. } => ErrorSeverity::Low,
             Self::MultipleErrors { errors, .. } => {
                 errors.iter()
                     .map(|e| e.severity())
@@ -258,6 +280,12 @@ impl AicedError {
             Self::UserInputError { input, expected, suggestion } => {
                 format!("Invalid input '{}': expected {}\n💡 {}", input, expected, suggestion)
             }
+            Self::Cancelled { operation } => {
+                format!("Cancelled by user during {}\n💡 Partial results were saved with the run record", operation)
+            }
+            Self::BudgetExceeded { scope, limit, spent } => {
+                format!("Budget exceeded: {} spend ${:.4} reached the ${:.4} cap\n💡 Raise the cap in the [budget] section or wait for the next day", scope, spent, limit)
+            }
             Self::MultipleErrors { errors, context } => {
                 let mut msg = format!("Multiple errors occurred during {}:\n", context);
                 for (i, error) in errors.iter().enumerate() {
@@ -0,0 +1,112 @@
+use serde_json::{json, Value};
+use crate::structs::ai::tool_definition::ToolDefinition;
+
+pub const REPORT_CHANGE_TOOL: &str = "report_change";
+pub const REPORT_TECHNOLOGY_STACK_TOOL: &str = "report_technology_stack";
+pub const REPORT_SUMMARY_TOOL: &str = "report_summary";
+
+/// Tools for the structured output mode. The input schemas mirror the serde layout of
+/// `FileChange`, `LineChange` and `TechnologyStack`, so tool arguments deserialize into them directly.
+pub fn analysis_tools(categories: &[&str]) -> Vec<ToolDefinition> {
+    vec![
+        ToolDefinition {
+            name: REPORT_CHANGE_TOOL.to_string(),
+            description: "Report one proposed change to one file. Call once per change.".to_string(),
+            input_schema: report_change_schema(categories),
+        },
+        ToolDefinition {
+            name: REPORT_TECHNOLOGY_STACK_TOOL.to_string(),
+            description: "Report the technology stack detected in the repository. Call exactly once.".to_string(),
+            input_schema: technology_stack_schema(),
+        },
+        ToolDefinition {
+            name: REPORT_SUMMARY_TOOL.to_string(),
+            description: "Report the analysis summary with counts by category. Call exactly once, after all changes.".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "summary": { "type": "string" }
+                },
+                "required": ["summary"]
+            }),
+        },
+    ]
+}
+
+fn report_change_schema(categories: &[&str]) -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "action": { "type": "string", "enum": ["modify_file", "create_file", "delete_file"] },
+            "file_path": { "type": "string", "description": "Exact file path as given in the prompt" },
+            "reason": { "type": "string", "description": "Explanation of the issue and the solution" },
+            "severity": { "type": "string", "enum": ["critical", "high", "medium", "low"] },
+            "category": {
+                "type": "string",
+                "enum": categories
+            },
+            "line_changes": {
+                "type": "array",
+                "description": "Required for modify_file. Line numbers are 1-based and refer to the original file.",
+                "items": line_change_schema()
+            },
+            "content": { "type": "string", "description": "Complete file content, required for create_file" }
+        },
+        "required": ["action", "file_path", "reason", "severity", "category"]
+    })
+}
+
+fn line_change_schema() -> Value {
+    let lines = json!({ "type": "array", "items": { "type": "string" } });
+
+    json!({
+        "type": "object",
+        "properties": {
+            "action": {
+                "type": "string",
+                "enum": [
+                    "replace", "insert_after", "insert_before", "delete",
+                    "replace_range", "insert_many_after", "insert_many_before", "delete_many"
+                ]
+            },
+            "line_number": { "type": "integer", "minimum": 1, "description": "For single-line actions and insert_many_*" },
+            "start_line": { "type": "integer", "minimum": 1, "description": "For replace_range and delete_many" },
+            "end_line": { "type": "integer", "minimum": 1, "description": "For replace_range and delete_many" },
+            "old_content": {
+                "description": "Exact current content: a string for replace, an array of lines for replace_range"
+            },
+            "new_content": {
+                "description": "Replacement content: a string for replace and insert_after/insert_before, an array of lines for replace_range"
+            },
+            "new_lines": lines
+        },
+        "required": ["action"]
+    })
+}
+
+fn technology_stack_schema() -> Value {
+    let text = json!({ "type": "string" });
+    let map = json!({ "type": "object", "additionalProperties": { "type": "string" } });
+
+    json!({
+        "type": "object",
+        "properties": {
+            "primary_language": text,
+            "framework": text,
+            "runtime": text,
+            "package_manager": text,
+            "database": text,
+            "orm": text,
+            "testing": text,
+            "build_tools": text,
+            "linting": text,
+            "containerization": text,
+            "cloud_services": text,
+            "authentication": text,
+            "api_type": text,
+            "architecture_pattern": text,
+            "dependencies": map,
+            "critical_configs": map
+        }
+    })
+}
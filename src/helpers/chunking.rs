@@ -0,0 +1,214 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use crate::config::constants::MODULE_MANIFESTS;
+use crate::enums::chunk_strategy::ChunkStrategy;
+use crate::helpers::{prompt_generator, token_estimator};
+use crate::structs::batch_plan::BatchPlan;
+use crate::structs::file_info::FileInfo;
+
+/// Splits the files into batches whose estimated prompt size stays within `available` tokens.
+/// Files that are larger than a whole batch are left out of the batches and listed in
+/// `oversized` instead; read-only context files that do not fit are dropped.
+pub fn split_into_batches(files: &[FileInfo], repo_path: &str, strategy: ChunkStrategy, available: u32) -> BatchPlan {
+    let groups = match strategy {
+        ChunkStrategy::Single => vec![files.to_vec()],
+        ChunkStrategy::ByDirectory | ChunkStrategy::Smart => group_by(files, parent_dir),
+        ChunkStrategy::ByModule => {
+            let mut module_roots = HashMap::new();
+            group_by(files, |file| module_root(file, Path::new(repo_path), &mut module_roots))
+        }
+    };
+
+    let mut batches = Vec::new();
+    let mut oversized = Vec::new();
+    let mut current: Vec<FileInfo> = Vec::new();
+    let mut current_tokens = 0;
+
+    for group in groups {
+        let sized: Vec<(FileInfo, u32)> = group
+            .into_iter()
+            .filter_map(|file| {
+                let tokens = token_estimator::estimate_tokens(&prompt_generator::generate_file_section(&file, repo_path));
+                if tokens > available {
+                    if !file.read_only {
+                        oversized.push((file, tokens));
+                    }
+                    return None;
+                }
+                Some((file, tokens))
+            })
+            .collect();
+        let group_tokens: u32 = sized.iter().map(|(_, tokens)| tokens).sum();
+
+        // Smart packing keeps a directory in one batch whenever it fits into a fresh one;
+        // the other strategies never share a batch between groups.
+        let starts_new_batch = match strategy {
+            ChunkStrategy::Smart => current_tokens + group_tokens > available && group_tokens <= available,
+            _ => true,
+        };
+        if starts_new_batch && !current.is_empty() {
+            batches.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+
+        for (file, tokens) in sized {
+            if current_tokens + tokens > available && !current.is_empty() {
+                batches.push(std::mem::take(&mut current));
+                current_tokens = 0;
+            }
+            current_tokens += tokens;
+            current.push(file);
+        }
+    }
+
+    if !current.is_empty() {
+        batches.push(current);
+    }
+
+    // A batch of context files alone has nothing to analyze.
+    batches.retain(|batch| batch.iter().any(|file| !file.read_only));
+    BatchPlan { batches, oversized }
+}
+
+fn group_by<F>(files: &[FileInfo], mut key: F) -> Vec<Vec<FileInfo>>
+where
+    F: FnMut(&FileInfo) -> PathBuf,
+{
+    let mut groups: BTreeMap<PathBuf, Vec<FileInfo>> = BTreeMap::new();
+    for file in files {
+        groups.entry(key(file)).or_default().push(file.clone());
+    }
+    groups.into_values().collect()
+}
+
+fn parent_dir(file: &FileInfo) -> PathBuf {
+    Path::new(&file.path).parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+/// The nearest directory above the file that holds a package manifest, or the top-level
+/// directory of the repository when there is none.
+fn module_root(file: &FileInfo, repo_path: &Path, cache: &mut HashMap<PathBuf, bool>) -> PathBuf {
+    let path = Path::new(&file.path);
+
+    for dir in path.ancestors().skip(1) {
+        if !dir.starts_with(repo_path) || dir == repo_path {
+            break;
+        }
+        let has_manifest = *cache
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| MODULE_MANIFESTS.iter().any(|manifest| dir.join(manifest).is_file()));
+        if has_manifest {
+            return dir.to_path_buf();
+        }
+    }
+
+    match path.strip_prefix(repo_path).ok().and_then(|relative| relative.components().next()) {
+        Some(top_level) if path.parent() != Some(repo_path) => repo_path.join(top_level),
+        _ => repo_path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::split_into_batches;
+    use crate::enums::chunk_strategy::ChunkStrategy;
+    use crate::helpers::{prompt_generator, token_estimator};
+    use crate::structs::file_info::FileInfo;
+
+    const REPO: &str = "/repo";
+
+    fn file(path: &str, lines: usize) -> FileInfo {
+        FileInfo { path: format!("{}/{}", REPO, path), content: "let x = 1;\n".repeat(lines), read_only: false }
+    }
+
+    fn tokens(files: &[&FileInfo]) -> u32 {
+        files.iter().map(|file| token_estimator::estimate_tokens(&prompt_generator::generate_file_section(file, REPO))).sum()
+    }
+
+    fn paths(batches: &[Vec<FileInfo>]) -> Vec<Vec<&str>> {
+        batches.iter().map(|batch| batch.iter().map(|file| file.path.trim_start_matches("/repo/")).collect()).collect()
+    }
+
+    #[test]
+    fn smart_packs_small_directories_together() {
+        let files = [file("a/one.rs", 5), file("b/two.rs", 5)];
+        let plan = split_into_batches(&files, REPO, ChunkStrategy::Smart, 10_000);
+
+        assert_eq!(paths(&plan.batches), vec![vec!["a/one.rs", "b/two.rs"]]);
+    }
+
+    #[test]
+    fn smart_keeps_a_directory_in_one_batch_within_the_budget() {
+        let files = [file("a/one.rs", 20), file("b/two.rs", 20), file("b/three.rs", 20)];
+        let budget = tokens(&[&files[1], &files[2]]) + 5;
+        let plan = split_into_batches(&files, REPO, ChunkStrategy::Smart, budget);
+
+        assert_eq!(paths(&plan.batches), vec![vec!["a/one.rs"], vec!["b/two.rs", "b/three.rs"]]);
+        assert!(plan.batches.iter().all(|batch| tokens(&batch.iter().collect::<Vec<_>>()) <= budget));
+        assert!(plan.oversized.is_empty());
+    }
+
+    #[test]
+    fn by_directory_never_shares_a_batch_between_directories() {
+        let files = [file("a/one.rs", 5), file("b/two.rs", 5)];
+        let plan = split_into_batches(&files, REPO, ChunkStrategy::ByDirectory, 10_000);
+
+        assert_eq!(paths(&plan.batches), vec![vec!["a/one.rs"], vec!["b/two.rs"]]);
+    }
+
+    #[test]
+    fn a_directory_larger_than_the_budget_is_split() {
+        let files = [file("a/one.rs", 20), file("a/two.rs", 20), file("a/three.rs", 20)];
+        let budget = tokens(&[&files[0], &files[1]]);
+        let plan = split_into_batches(&files, REPO, ChunkStrategy::Smart, budget);
+
+        assert_eq!(plan.batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 1]);
+    }
+
+    #[test]
+    fn oversize_files_are_listed_and_oversize_context_is_dropped() {
+        let mut context = file("a/context.rs", 200);
+        context.read_only = true;
+        let files = [file("a/small.rs", 5), file("a/huge.rs", 200), context];
+        let budget = tokens(&[&files[0]]) + 50;
+        let plan = split_into_batches(&files, REPO, ChunkStrategy::Smart, budget);
+
+        assert_eq!(paths(&plan.batches), vec![vec!["a/small.rs"]]);
+        assert_eq!(plan.oversized.len(), 1);
+        assert_eq!(plan.oversized[0].0.path, "/repo/a/huge.rs");
+        assert_eq!(plan.oversized[0].1, tokens(&[&files[1]]));
+    }
+
+    #[test]
+    fn batches_of_context_files_alone_are_dropped() {
+        let mut context = file("b/context.rs", 5);
+        context.read_only = true;
+        let files = [file("a/one.rs", 5), context];
+        let plan = split_into_batches(&files, REPO, ChunkStrategy::ByDirectory, 10_000);
+
+        assert_eq!(paths(&plan.batches), vec![vec!["a/one.rs"]]);
+    }
+
+    #[test]
+    fn by_module_groups_files_under_their_nearest_manifest() {
+        let repo = tempfile::tempdir().unwrap();
+        let repo_path = repo.path().to_str().unwrap();
+        fs::create_dir_all(repo.path().join("crates/x/src")).unwrap();
+        fs::write(repo.path().join("crates/x/Cargo.toml"), "").unwrap();
+        let file = |path: &str| FileInfo { path: format!("{}/{}", repo_path, path), content: "fn main() {}\n".to_string(), read_only: false };
+        let files = [file("crates/x/src/lib.rs"), file("crates/y/lib.rs"), file("crates/x/build.rs"), file("main.rs")];
+
+        let plan = split_into_batches(&files, repo_path, ChunkStrategy::ByModule, 10_000);
+        let batches: Vec<Vec<&str>> = plan.batches
+            .iter()
+            .map(|batch| batch.iter().map(|file| file.path.trim_start_matches(repo_path)).collect())
+            .collect();
+
+        assert_eq!(batches, vec![
+            vec!["/main.rs"],
+            vec!["/crates/y/lib.rs"],
+            vec!["/crates/x/src/lib.rs", "/crates/x/build.rs"],
+        ]);
+    }
+}
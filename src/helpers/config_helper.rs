@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use crate::structs::config::model_pricing::ModelPricing;
+
 pub struct ConfigHelper;
 
 impl ConfigHelper {
@@ -46,8 +50,8 @@ impl ConfigHelper {
         true
     }
 
-    pub fn default_chunk_strategy() -> String {
-        "smart".to_string()
+    pub fn default_max_concurrent_batches() -> usize {
+        1
     }
 
     pub fn default_format() -> String {
@@ -69,4 +73,152 @@ impl ConfigHelper {
     pub fn default_severity_threshold() -> String {
         "low".to_string()
     }
-}
\ No newline at end of file
+
+    pub fn default_thinking() -> bool {
+        true
+    }
+
+    pub fn default_thinking_budget() -> u32 {
+        63999
+    }
+
+    pub fn default_prompt_caching() -> bool {
+        true
+    }
+
+    pub fn default_request_timeout_secs() -> u64 {
+        900
+    }
+
+    pub fn default_connect_timeout_secs() -> u64 {
+        30
+    }
+
+    /// Long enough for a model that thinks before its first token; Anthropic also sends pings meanwhile.
+    pub fn default_read_timeout_secs() -> u64 {
+        300
+    }
+
+    pub fn default_max_retries() -> u32 {
+        5
+    }
+
+    pub fn default_retry_base_delay_ms() -> u64 {
+        1000
+    }
+
+    pub fn default_retry_max_delay_ms() -> u64 {
+        60000
+    }
+
+    pub fn default_max_continuations() -> u32 {
+        3
+    }
+
+    pub fn default_prompt_language() -> String {
+        "English".to_string()
+    }
+
+    /// Parses sizes like `1MB`, `512 KB`, `1.5mb` or `2048` (bytes); units are binary.
+    pub fn parse_size(value: &str) -> Result<u64, String> {
+        let value = value.trim().to_uppercase();
+        let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+        let (number, unit) = value.split_at(split);
+        let number: f64 = number.parse().map_err(|_| format!("'{}' is not a size like 512KB or 1MB", value))?;
+        let multiplier: u64 = match unit.trim().trim_end_matches("IB").trim_end_matches('B') {
+            "" => 1,
+            "K" => 1024,
+            "M" => 1024 * 1024,
+            "G" => 1024 * 1024 * 1024,
+            _ => return Err(format!("unknown size unit in '{}', use B, KB, MB or GB", value)),
+        };
+        Ok((number * multiplier as f64) as u64)
+    }
+
+    pub fn format_size(bytes: u64) -> String {
+        match bytes {
+            b if b >= 1024 * 1024 => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+            b if b >= 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+            b => format!("{} B", b),
+        }
+    }
+
+    /// `security`, `clean-code` or `Clean_Code` become the `SECURITY` / `CLEAN_CODE` form used in prompts.
+    pub fn normalize_category(name: &str) -> String {
+        name.trim().to_uppercase().replace(['-', ' '], "_")
+    }
+
+    /// Expands a leading `~/` to the home directory.
+    pub fn expand_home(path: &str) -> PathBuf {
+        match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|d| d.join(rest)).unwrap_or_else(|| PathBuf::from(path)),
+            None => PathBuf::from(path),
+        }
+    }
+
+    /// Copies every key of `defaults` that `target` does not set. Nested tables are merged
+    /// the same way; arrays and plain values set in `target` win as a whole.
+    pub fn merge_missing(target: &mut toml::Table, defaults: &toml::Table) {
+        for (key, default) in defaults {
+            match (target.get_mut(key), default) {
+                (None, _) => {
+                    target.insert(key.clone(), default.clone());
+                }
+                (Some(toml::Value::Table(nested)), toml::Value::Table(nested_default)) => {
+                    Self::merge_missing(nested, nested_default);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Built-in prices (USD per million tokens); entries under `[pricing]` take precedence.
+    pub fn default_model_pricing() -> HashMap<String, ModelPricing> {
+        [
+            ("claude-opus-4-20250514", 15.0, 75.0),
+            ("claude-sonnet-4-20250514", 3.0, 15.0),
+            ("claude-3-7-sonnet-20250219", 3.0, 15.0),
+            ("claude-3-5-haiku-20241022", 0.8, 4.0),
+            ("gpt-4o", 2.5, 10.0),
+            ("gpt-4o-mini", 0.15, 0.6),
+        ]
+        .into_iter()
+        .map(|(model, input, output)| (model.to_string(), ModelPricing {
+            input_per_million: input,
+            output_per_million: output,
+            cache_write_per_million: None,
+            cache_read_per_million: None,
+        }))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigHelper;
+
+    #[test]
+    fn sizes_take_binary_units_in_any_case() {
+        assert_eq!(ConfigHelper::parse_size("2048"), Ok(2048));
+        assert_eq!(ConfigHelper::parse_size("10k"), Ok(10 * 1024));
+        assert_eq!(ConfigHelper::parse_size("512 KB"), Ok(512 * 1024));
+        assert_eq!(ConfigHelper::parse_size("1.5MB"), Ok(1024 * 1024 * 3 / 2));
+        assert_eq!(ConfigHelper::parse_size("1MiB"), Ok(1024 * 1024));
+        assert_eq!(ConfigHelper::parse_size(" 2gb "), Ok(2 * 1024 * 1024 * 1024));
+        assert_eq!(ConfigHelper::parse_size("100B"), Ok(100));
+    }
+
+    #[test]
+    fn unknown_units_are_rejected() {
+        assert!(ConfigHelper::parse_size("10 TB").unwrap_err().contains("unknown size unit"));
+        assert!(ConfigHelper::parse_size("10 parsecs").is_err());
+    }
+
+    #[test]
+    fn sizes_without_a_number_are_rejected() {
+        assert!(ConfigHelper::parse_size("MB").unwrap_err().contains("is not a size"));
+        assert!(ConfigHelper::parse_size("").is_err());
+        assert!(ConfigHelper::parse_size("-1KB").is_err());
+        assert!(ConfigHelper::parse_size("1.2.3KB").is_err());
+    }
+}
@@ -0,0 +1,63 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::errors::{AicedError, AicedResult};
+use crate::helpers::config_helper::ConfigHelper;
+
+pub fn head_commit(repo_path: &str) -> AicedResult<String> {
+    run_git(repo_path, &["rev-parse", "HEAD"]).map(|output| output.trim().to_string())
+}
+
+/// Files under `repo_path` that differ from `since` in the working tree, plus untracked
+/// files. Deleted files are left out; the paths are absolute.
+pub fn changed_files(repo_path: &str, since: &str) -> AicedResult<Vec<PathBuf>> {
+    let commit = format!("{}^{{commit}}", since);
+    run_git(repo_path, &["rev-parse", "--verify", "--quiet", &commit])
+        .map_err(|_| AicedError::repo_error(repo_path, "resolve git ref", &format!("'{}' is not a known commit, branch or tag", since)))?;
+
+    let diff = run_git(repo_path, &["diff", "--name-only", "--relative", "--diff-filter=d", since, "--"])?;
+    let untracked = run_git(repo_path, &["ls-files", "--others", "--exclude-standard"])?;
+
+    let mut paths: Vec<PathBuf> = diff
+        .lines()
+        .chain(untracked.lines())
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Path::new(repo_path).join(line.trim()))
+        .collect();
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// The user's global excludes file: `core.excludesFile`, or git's default location under
+/// `$XDG_CONFIG_HOME` or `~/.config`.
+pub fn global_excludes_file(repo_path: &str) -> Option<PathBuf> {
+    if let Ok(configured) = run_git(repo_path, &["config", "--path", "--get", "core.excludesFile"]) {
+        if !configured.trim().is_empty() {
+            return Some(ConfigHelper::expand_home(configured.trim()));
+        }
+    }
+
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))?;
+    Some(config_dir.join("git").join("ignore"))
+}
+
+fn run_git(repo_path: &str, args: &[&str]) -> AicedResult<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| AicedError::system_error("run git", &e.to_string()))?;
+
+    if !output.status.success() {
+        return Err(AicedError::repo_error(
+            repo_path,
+            &format!("git {}", args.join(" ")),
+            String::from_utf8_lossy(&output.stderr).trim(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
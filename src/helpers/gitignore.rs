@@ -0,0 +1,211 @@
+/// Matches `text` against a gitignore glob. `*` and `?` never match `/`, `**` spans
+/// directories when it is a whole path segment, `[...]` is a character class and `\`
+/// escapes the next character. Classes may contain POSIX classes such as `[:alpha:]`.
+pub fn wildmatch(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    match_from(&pattern, 0, &text, 0)
+}
+
+fn match_from(p: &[char], mut pi: usize, t: &[char], mut ti: usize) -> bool {
+    while pi < p.len() {
+        match p[pi] {
+            '*' => {
+                let start = pi;
+                while pi < p.len() && p[pi] == '*' {
+                    pi += 1;
+                }
+
+                let whole_segment = pi - start >= 2 && (start == 0 || p[start - 1] == '/');
+                if whole_segment && pi == p.len() {
+                    return true;
+                }
+                if whole_segment && p[pi] == '/' {
+                    // "**/" matches zero or more directories.
+                    let rest = pi + 1;
+                    return match_from(p, rest, t, ti)
+                        || (ti..t.len()).any(|i| t[i] == '/' && match_from(p, rest, t, i + 1));
+                }
+
+                // Any other run of asterisks stays within one path segment.
+                for i in ti..=t.len() {
+                    if match_from(p, pi, t, i) {
+                        return true;
+                    }
+                    if i < t.len() && t[i] == '/' {
+                        break;
+                    }
+                }
+                return false;
+            }
+            '?' => {
+                if ti >= t.len() || t[ti] == '/' {
+                    return false;
+                }
+                pi += 1;
+                ti += 1;
+            }
+            '[' if match_class(p, pi, None).is_some() => {
+                let Some((matched, next)) = match_class(p, pi, t.get(ti).copied()) else {
+                    return false;
+                };
+                if !matched {
+                    return false;
+                }
+                pi = next;
+                ti += 1;
+            }
+            c => {
+                let (literal, width) = match c {
+                    '\\' if pi + 1 < p.len() => (p[pi + 1], 2),
+                    _ => (c, 1),
+                };
+                if t.get(ti) != Some(&literal) {
+                    return false;
+                }
+                pi += width;
+                ti += 1;
+            }
+        }
+    }
+
+    ti == t.len()
+}
+
+/// Matches `c` against the class starting at `p[start]`, returning whether it matched and
+/// the index after the class, or `None` when the class is not closed.
+fn match_class(p: &[char], start: usize, c: Option<char>) -> Option<(bool, usize)> {
+    let mut i = start + 1;
+    let negated = matches!(p.get(i), Some('!') | Some('^'));
+    if negated {
+        i += 1;
+    }
+
+    let mut found = false;
+    let mut first = true;
+    loop {
+        let mut low = *p.get(i)?;
+        if low == ']' && !first {
+            break;
+        }
+        first = false;
+        if low == '[' && p.get(i + 1) == Some(&':') {
+            if let Some(end) = (i + 2..p.len().saturating_sub(1)).find(|&j| p[j] == ':' && p[j + 1] == ']') {
+                let name: String = p[i + 2..end].iter().collect();
+                if c.is_some_and(|c| posix_class(&name, c)) {
+                    found = true;
+                }
+                i = end + 2;
+                continue;
+            }
+        }
+        if low == '\\' {
+            i += 1;
+            low = *p.get(i)?;
+        }
+
+        let mut high = low;
+        if p.get(i + 1) == Some(&'-') && p.get(i + 2).is_some_and(|&next| next != ']') {
+            i += 2;
+            high = p[i];
+            if high == '\\' {
+                i += 1;
+                high = *p.get(i)?;
+            }
+        }
+
+        if c.is_some_and(|c| low <= c && c <= high) {
+            found = true;
+        }
+        i += 1;
+    }
+
+    Some((c.is_some_and(|c| c != '/' && found != negated), i + 1))
+}
+
+/// Whether `c` belongs to the POSIX class `name`; unknown classes match nothing.
+fn posix_class(name: &str, c: char) -> bool {
+    match name {
+        "alnum" => c.is_ascii_alphanumeric(),
+        "alpha" => c.is_ascii_alphabetic(),
+        "blank" => c == ' ' || c == '\t',
+        "cntrl" => c.is_ascii_control(),
+        "digit" => c.is_ascii_digit(),
+        "graph" => c.is_ascii_graphic(),
+        "lower" => c.is_ascii_lowercase(),
+        "print" => c.is_ascii_graphic() || c == ' ',
+        "punct" => c.is_ascii_punctuation(),
+        "space" => c.is_ascii_whitespace() || c == '\x0b',
+        "upper" => c.is_ascii_uppercase(),
+        "xdigit" => c.is_ascii_hexdigit(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wildmatch;
+
+    #[test]
+    fn star_and_question_mark_stay_within_a_segment() {
+        assert!(wildmatch("*.rs", "main.rs"));
+        assert!(!wildmatch("*.rs", "src/main.rs"));
+        assert!(wildmatch("src/*.rs", "src/main.rs"));
+        assert!(!wildmatch("src/*.rs", "src/bin/main.rs"));
+        assert!(wildmatch("ma?n.rs", "main.rs"));
+        assert!(!wildmatch("src?main.rs", "src/main.rs"));
+    }
+
+    #[test]
+    fn double_star_spans_directories() {
+        assert!(wildmatch("**/build", "build"));
+        assert!(wildmatch("**/build", "a/b/build"));
+        assert!(wildmatch("docs/**", "docs/a/b.md"));
+        assert!(wildmatch("a/**/b", "a/b"));
+        assert!(wildmatch("a/**/b", "a/x/y/b"));
+        assert!(!wildmatch("a/**/b", "a/x/c"));
+        // Not a whole segment, so it acts like a single star.
+        assert!(wildmatch("a**b", "axxb"));
+        assert!(!wildmatch("a**b", "a/b"));
+    }
+
+    #[test]
+    fn classes_ranges_and_negation() {
+        assert!(wildmatch("file[0-9].txt", "file7.txt"));
+        assert!(!wildmatch("file[0-9].txt", "filex.txt"));
+        assert!(wildmatch("file[!0-9].txt", "filex.txt"));
+        assert!(wildmatch("file[^0-9].txt", "filex.txt"));
+        assert!(!wildmatch("file[!0-9].txt", "file7.txt"));
+        assert!(wildmatch("[]]", "]"));
+        assert!(wildmatch("[a-]", "-"));
+        assert!(!wildmatch("a[!x]b", "a/b"));
+    }
+
+    #[test]
+    fn posix_classes() {
+        assert!(wildmatch("[[:alpha:]]*.log", "debug.log"));
+        assert!(!wildmatch("[[:alpha:]]*.log", "1.log"));
+        assert!(wildmatch("v[[:digit:]][[:digit:]]", "v42"));
+        assert!(wildmatch("[[:upper:][:digit:]]x", "Ax"));
+        assert!(wildmatch("[[:upper:][:digit:]]x", "7x"));
+        assert!(!wildmatch("[[:upper:][:digit:]]x", "ax"));
+        assert!(wildmatch("[![:space:]]", "a"));
+        assert!(!wildmatch("[[:nonsense:]]", "a"));
+    }
+
+    #[test]
+    fn escapes_match_literally() {
+        assert!(wildmatch("\\*.rs", "*.rs"));
+        assert!(!wildmatch("\\*.rs", "main.rs"));
+        assert!(wildmatch("\\#notes", "#notes"));
+        assert!(wildmatch("a\\?", "a?"));
+        assert!(!wildmatch("a\\?", "ab"));
+        assert!(wildmatch("[\\]]", "]"));
+    }
+
+    #[test]
+    fn unclosed_class_is_literal() {
+        assert!(wildmatch("[abc", "[abc"));
+        assert!(!wildmatch("[abc", "a"));
+    }
+}
@@ -1,2 +1,9 @@
 pub mod prompt_generator;
-pub mod config_helper;
\ No newline at end of file
+pub mod config_helper;
+pub mod token_estimator;
+pub mod analysis_tools;
+pub mod chunking;
+pub mod git_helper;
+pub mod prompt_template;
+pub mod project_context;
+pub mod gitignore;
@@ -0,0 +1,169 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use serde_json::Value;
+use crate::config::constants::{
+    CONTEXT_CONFIGS, CONTEXT_LOCKFILES, CONTEXT_SCAN_DEPTH, CONTEXT_SKIP_DIRS, MAX_CONTEXT_CHARS,
+    MAX_CONTEXT_FILE_CHARS, MODULE_MANIFESTS,
+};
+use crate::enums::context_file_kind::ContextFileKind;
+use crate::structs::context_file::ContextFile;
+use crate::structs::ignore_matcher::IgnoreMatcher;
+
+/// Manifests, lockfile summaries and CI/container configs of the repository, sorted by path.
+/// Files and directories left out by the ignore rules are not looked at. Each file and the
+/// context as a whole are capped so that a monorepo cannot crowd out the code.
+pub fn collect(repo_path: &str) -> Vec<ContextFile> {
+    let root = Path::new(repo_path);
+    let mut matcher = IgnoreMatcher::for_repo(repo_path);
+    let mut found = Vec::new();
+    walk(root, root, 0, &mut matcher, &mut found);
+    found.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut total = 0;
+    let mut files = Vec::new();
+    for file in found {
+        let chars = file.content.chars().count();
+        if total + chars > MAX_CONTEXT_CHARS {
+            log::warn!("⚠️ Project context is limited to {} characters, leaving out {} and later files", MAX_CONTEXT_CHARS, file.path);
+            break;
+        }
+        total += chars;
+        files.push(file);
+    }
+    files
+}
+
+fn walk(root: &Path, dir: &Path, depth: usize, matcher: &mut IgnoreMatcher, found: &mut Vec<ContextFile>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    matcher.load_dir(&relative_path(root, dir));
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let relative = relative_path(root, &path);
+        if matcher.is_ignored(&relative, path.is_dir()) {
+            continue;
+        }
+
+        if path.is_dir() {
+            let is_config_dir = CONTEXT_CONFIGS.iter().any(|config| config.ends_with('/') && config.starts_with(&format!("{}/", relative)));
+            let hidden = name.starts_with('.') && !is_config_dir;
+            if depth < CONTEXT_SCAN_DEPTH && !hidden && !CONTEXT_SKIP_DIRS.contains(&name.as_str()) {
+                walk(root, &path, depth + 1, matcher, found);
+            }
+            continue;
+        }
+
+        let kind = if MODULE_MANIFESTS.contains(&name.as_str()) {
+            ContextFileKind::Manifest
+        } else if CONTEXT_LOCKFILES.contains(&name.as_str()) {
+            ContextFileKind::LockfileSummary
+        } else if is_ci_config(&relative, &name) {
+            ContextFileKind::CiConfig
+        } else {
+            continue;
+        };
+
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let content = match kind {
+            ContextFileKind::LockfileSummary => summarize_lockfile(&name, &content),
+            _ => content,
+        };
+        if content.trim().is_empty() {
+            continue;
+        }
+
+        found.push(ContextFile { path: relative, kind, content: truncate(content.trim(), MAX_CONTEXT_FILE_CHARS) });
+    }
+}
+
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).to_string_lossy().replace('\\', "/")
+}
+
+fn is_ci_config(relative: &str, name: &str) -> bool {
+    CONTEXT_CONFIGS.iter().any(|config| match config.strip_suffix('/') {
+        Some(dir) => relative.starts_with(&format!("{}/", dir)) && (name.ends_with(".yml") || name.ends_with(".yaml")),
+        None => relative == *config || name == *config,
+    })
+}
+
+/// The resolved packages of a lockfile as `name version` lines.
+fn summarize_lockfile(name: &str, content: &str) -> String {
+    let packages: BTreeSet<String> = match name {
+        "Cargo.lock" | "poetry.lock" => toml::from_str::<toml::Value>(content)
+            .ok()
+            .and_then(|lock| lock.get("package").and_then(|p| p.as_array()).cloned())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|package| Some(format!("{} {}", package.get("name")?.as_str()?, package.get("version")?.as_str()?)))
+            .collect(),
+        "package-lock.json" => serde_json::from_str::<Value>(content)
+            .ok()
+            .and_then(|lock| lock.get("packages").or_else(|| lock.get("dependencies")).and_then(|p| p.as_object()).cloned())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|(key, package)| {
+                let name = key.strip_prefix("node_modules/").unwrap_or(key);
+                // Top-level packages only; nested copies and the root entry add nothing.
+                if name.is_empty() || name.contains("node_modules/") {
+                    return None;
+                }
+                Some(format!("{} {}", name, package.get("version")?.as_str()?))
+            })
+            .collect(),
+        "go.sum" => content
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let module = parts.next()?;
+                let version = parts.next()?.trim_end_matches("/go.mod");
+                Some(format!("{} {}", module, version))
+            })
+            .collect(),
+        _ => BTreeSet::new(),
+    };
+
+    if packages.is_empty() {
+        return String::new();
+    }
+    format!("{} packages:\n{}", packages.len(), packages.into_iter().collect::<Vec<_>>().join("\n"))
+}
+
+fn truncate(content: &str, max_chars: usize) -> String {
+    match content.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}\n... (truncated)", &content[..index]),
+        None => content.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::{collect, truncate};
+    use crate::config::constants::MAX_CONTEXT_CHARS;
+
+    #[test]
+    fn truncates_on_a_char_boundary() {
+        assert_eq!(truncate("héllo", 2), "hé\n... (truncated)");
+        assert_eq!(truncate("héllo", 5), "héllo");
+    }
+
+    #[test]
+    fn the_total_cap_counts_chars_not_bytes() {
+        let repo = tempfile::tempdir().unwrap();
+        let manifest = "é".repeat(MAX_CONTEXT_CHARS / 5);
+        for module in ["a", "b", "c", "d"] {
+            fs::create_dir_all(repo.path().join(module)).unwrap();
+            fs::write(repo.path().join(module).join("package.json"), &manifest).unwrap();
+        }
+
+        let files = collect(repo.path().to_str().unwrap());
+        assert_eq!(files.len(), 4);
+    }
+}
@@ -1,35 +1,60 @@
 use std::path::PathBuf;
+use crate::structs::context_file::ContextFile;
 use crate::structs::file_info::FileInfo;
 
-pub fn generate_analysis_user_prompt(files: Vec<FileInfo>, repo_path: &str) -> String {
+/// The file sections of the analysis prompt, filled into the `{{files}}` placeholder of the user template.
+pub fn generate_files_block(files: &[FileInfo], context: &[ContextFile], repo_path: &str) -> String {
     let estimated_size = files.iter().map(|f| f.content.len() * 2).sum::<usize>();
-    let mut prompt = String::with_capacity(estimated_size);
-    prompt.push_str("Analyze this code:\n");
+    let mut block = String::with_capacity(estimated_size);
+    block.push_str(&generate_context_section(context));
 
     for file in files {
-        let path = file.path.replace(repo_path, "");
-        let line_count = file.content.lines().count();
+        block.push_str(&generate_file_section(file, repo_path));
+    }
 
-        prompt.push_str("File: ");
-        prompt.push_str(&path);
-        prompt.push_str(" \nTotal lines: ");
-        prompt.push_str(&line_count.to_string());
-        prompt.push('\n');
+    if files.iter().any(|file| file.read_only) {
+        block.push_str("Files marked as read-only context are unchanged code shown only to explain the code around the changes. Do NOT propose changes to them.\n\n");
+    }
+    block
+}
 
-        for (i, line) in file.content.lines().enumerate() {
-            prompt.push_str(&format!("{:4}: {}\n", i + 1, line));
-        }
+/// Manifests, lockfile summaries and CI/container configs, without line numbers since
+/// nothing may be changed in them.
+pub fn generate_context_section(context: &[ContextFile]) -> String {
+    if context.is_empty() {
+        return String::new();
+    }
 
-        prompt.push_str("\n=== END OF ");
-        prompt.push_str(&path);
-        prompt.push_str(" (lines 1-");
-        prompt.push_str(&line_count.to_string());
-        prompt.push_str(") ===\n\n");
+    let mut section = String::from("=== PROJECT CONTEXT (read-only, NOT editable) ===\n");
+    section.push_str("Use these files to report the technology stack and exact dependency versions. Do NOT propose changes to them.\n\n");
+    for file in context {
+        section.push_str(&format!("Context file ({}): {}\n{}\n=== END OF {} ===\n\n", file.kind, file.path, file.content, file.path));
     }
+    section.push_str("=== END OF PROJECT CONTEXT ===\n\n");
+    section
+}
 
-    prompt.push_str("CRITICAL: Use EXACT line numbers from above. If you reference line 562, ensure it exists in the file.\n\n");
-    prompt.push_str("If you cannot analyze the code, respond with: ANALYSIS_SUMMARY:\nUnable to analyze the provided code. Please ensure valid code was submitted.");
-    prompt
+pub fn generate_file_section(file: &FileInfo, repo_path: &str) -> String {
+    let path = file.path.replace(repo_path, "");
+    let line_count = file.content.lines().count();
+    let mut section = String::with_capacity(file.content.len() * 2);
+
+    section.push_str(if file.read_only { "File (read-only context): " } else { "File: " });
+    section.push_str(&path);
+    section.push_str(" \nTotal lines: ");
+    section.push_str(&line_count.to_string());
+    section.push('\n');
+
+    for (i, line) in file.content.lines().enumerate() {
+        section.push_str(&format!("{:4}: {}\n", i + 1, line));
+    }
+
+    section.push_str("\n=== END OF ");
+    section.push_str(&path);
+    section.push_str(" (lines 1-");
+    section.push_str(&line_count.to_string());
+    section.push_str(") ===\n\n");
+    section
 }
 
 pub fn generate_file_filter_user_prompt(file_paths: &Vec<PathBuf>, repo_path: &str) -> String {
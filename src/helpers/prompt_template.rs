@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+/// Checks that every `{{name}}` placeholder in the template is one of `allowed`.
+pub fn validate(template: &str, allowed: &[&str]) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after.find("}}").ok_or_else(|| "unclosed '{{' placeholder".to_string())?;
+        let name = after[..end].trim();
+        if !allowed.contains(&name) {
+            return Err(format!("unknown placeholder '{{{{{}}}}}', expected one of: {}", name, allowed.join(", ")));
+        }
+        rest = &after[end + 2..];
+    }
+    Ok(())
+}
+
+/// Replaces `{{name}}` placeholders with their values; unknown placeholders are left as they are.
+pub fn render(template: &str, variables: &HashMap<&str, String>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+        match variables.get(after[..end].trim()) {
+            Some(value) => output.push_str(value),
+            None => output.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+
+    output.push_str(rest);
+    output
+}
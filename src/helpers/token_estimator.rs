@@ -0,0 +1,16 @@
+use crate::config::constants::ESTIMATED_CHARS_PER_TOKEN;
+use crate::structs::ai::chat_message::ChatMessage;
+
+/// Offline approximation of a tokenizer. Source code tokenizes denser than prose,
+/// so this deliberately overestimates rather than underestimates.
+pub fn estimate_tokens(text: &str) -> u32 {
+    estimate_tokens_from_chars(text.chars().count())
+}
+
+pub fn estimate_tokens_from_chars(chars: usize) -> u32 {
+    (chars as f64 / ESTIMATED_CHARS_PER_TOKEN).ceil() as u32
+}
+
+pub fn estimate_message_tokens(messages: &[ChatMessage]) -> u32 {
+    messages.iter().map(|m| estimate_tokens(&m.content)).sum()
+}
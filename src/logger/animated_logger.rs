@@ -1,12 +1,16 @@
-use std::io::Write;
-use tokio::sync::mpsc;
+use std::io::{IsTerminal, Write};
+use tokio::sync::{mpsc, watch};
 use tokio::task::JoinHandle;
+use crate::config::constants::PROGRESS_LOG_INTERVAL_SECS;
+use crate::structs::stream_progress::StreamProgress;
 
 pub struct AnimatedLogger {
     message: String,
     animation_chars: Vec<&'static str>,
     stop_sender: Option<mpsc::UnboundedSender<()>>,
     task_handle: Option<JoinHandle<()>>,
+    progress: Option<watch::Receiver<Option<StreamProgress>>>,
+    is_terminal: bool,
 }
 
 impl AnimatedLogger {
@@ -18,24 +22,52 @@ impl AnimatedLogger {
             animation_chars,
             stop_sender: None,
             task_handle: None,
+            progress: None,
+            is_terminal: std::io::stderr().is_terminal(),
         }
     }
 
+    /// Shows live stream progress next to the spinner, or as periodic log lines when
+    /// stderr is not a terminal.
+    pub fn with_progress(mut self, progress: watch::Receiver<Option<StreamProgress>>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
     pub fn start(&mut self) {
         let (stop_tx, mut stop_rx) = mpsc::unbounded_channel();
         let message = self.message.clone();
         let animation_chars = self.animation_chars.clone();
+        let progress = self.progress.clone();
+        let is_terminal = self.is_terminal;
 
         let handle = tokio::spawn(async move {
             let mut frame = 0;
-            let mut interval = tokio::time::interval(tokio::time::Duration::from_millis(150));
+            let tick = if is_terminal {
+                tokio::time::Duration::from_millis(150)
+            } else {
+                tokio::time::Duration::from_secs(PROGRESS_LOG_INTERVAL_SECS)
+            };
+            let mut interval = tokio::time::interval(tick);
+            // The first tick completes immediately; a log line right at the start says nothing.
+            if !is_terminal {
+                interval.tick().await;
+            }
 
             loop {
                 tokio::select! {
                     _ = interval.tick() => {
-                        eprint!("\r{} {} ", message, animation_chars[frame]);
-                        let _ = std::io::stderr().flush();
-                        frame = (frame + 1) % animation_chars.len();
+                        let status = progress.as_ref().and_then(|p| p.borrow().as_ref().map(StreamProgress::summary));
+                        if is_terminal {
+                            eprint!("\r\x1b[K{} {} {}", message, animation_chars[frame], status.unwrap_or_default());
+                            let _ = std::io::stderr().flush();
+                            frame = (frame + 1) % animation_chars.len();
+                        } else {
+                            match status {
+                                Some(status) => log::info!("⏳ {}: {}", message, status),
+                                None => log::info!("⏳ {}...", message),
+                            }
+                        }
                     }
                     _ = stop_rx.recv() => {
                         break;
@@ -57,7 +89,16 @@ impl AnimatedLogger {
             let _ = handle.await;
         }
 
-        eprint!("\r\x1b[K✅  {}\n", final_message);
-        let _ = std::io::stderr().flush();
+        let final_message = match self.progress.as_ref().and_then(|p| p.borrow().as_ref().map(StreamProgress::summary)) {
+            Some(status) => format!("{} ({})", final_message, status),
+            None => final_message.to_string(),
+        };
+
+        if self.is_terminal {
+            eprint!("\r\x1b[K✅  {}\n", final_message);
+            let _ = std::io::stderr().flush();
+        } else {
+            log::info!("✅ {}", final_message);
+        }
     }
-}
\ No newline at end of file
+}
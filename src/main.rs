@@ -1,6 +1,7 @@
 
 use clap::Parser;
 use std::process;
+use crate::config::constants::EXIT_CODE_CANCELLED;
 use crate::errors::{AicedResult, ErrorHandler, ErrorSeverity};
 use crate::structs::cli::Cli;
 use crate::workers::command_runner::CommandRunner;
@@ -25,6 +26,11 @@ async fn main() {
         .init();
 
     if let Err(e) = run().await {
+        if e.is_cancelled() {
+            log::warn!("🛑 {}", e.user_message());
+            process::exit(EXIT_CODE_CANCELLED);
+        }
+
         ErrorHandler::handle_error(&e);
 
         let exit_code = match e.severity() {
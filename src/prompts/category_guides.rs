@@ -0,0 +1,27 @@
+/// What the model looks for in each analysis category; the system prompt lists only the
+/// selected ones.
+pub const CATEGORY_GUIDES: &[(&str, &str)] = &[
+    ("BUGS", r#"BUGS: Logic errors, null pointer exceptions, off-by-one errors, race conditions, unhandled errors, resource leaks"#),
+    ("SECURITY", r#"SECURITY: SQL injection, XSS, command injection, authentication and authorization flaws, secrets in code, unsafe deserialization"#),
+    ("PERFORMANCE", r#"PERFORMANCE: Memory leaks, inefficient algorithms, database query optimization, resource management"#),
+    ("CLEAN_CODE", r#"CLEAN_CODE: Based on Robert C. Martin's "Clean Code" book
+   - Variable and function names should be descriptive and pronounceable
+   - Functions should be small (ideally < 20 lines) and do one thing
+   - Avoid deep nesting (max 3-4 levels)
+   - Use meaningful comments only when code cannot be self-explanatory
+   - Consistent formatting and naming conventions
+   - Proper error handling without ignored exceptions
+   - Avoid magic numbers and strings"#),
+    ("ARCHITECTURE", r#"ARCHITECTURE: Design patterns and architectural concerns
+   - Data access logic should be separated from business logic
+   - Repository interfaces should be well-defined
+   - Dependency injection should be used for testability
+   - Business rules should not leak into data access layer
+   - Proper abstraction levels and interface segregation
+   - SOLID principles and command/query separation where applicable"#),
+    ("DUPLICATE_CODE", r#"DUPLICATE_CODE: Code repetition and maintainability
+   - Identify code blocks with >80% similarity
+   - Look for repeated business logic patterns
+   - Find opportunities to extract common functionality into utility functions or base classes
+   - Identify copy-paste programming instances"#),
+];
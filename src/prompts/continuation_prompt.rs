@@ -0,0 +1,3 @@
+pub const CONTINUATION_PROMPT: &str = r#"Your previous response was cut off because it reached the output token limit.
+Continue exactly where it stopped, starting with the next complete block.
+Do not repeat anything you already wrote, do not restate the summary or technology stack, and keep the same format."#;
@@ -0,0 +1,3 @@
+pub const MERGE_SUMMARY_PROMPT: &str = r#"You combine the summaries of a code analysis that was run separately on several parts of one repository.
+Write a single summary of the whole repository in plain text. Add up the counts by category across all parts (e.g., "Found 3 clean code violations, 2 duplicate code patterns, 1 security issue") and mention the most important findings.
+Do not invent findings that are not in the summaries, do not mention the parts or batches, and reply with the summary only."#;
@@ -1,2 +1,7 @@
 pub mod system_analysis_prompt;
 pub mod file_filter_system_prompt;
+pub mod continuation_prompt;
+pub mod system_analysis_tools_prompt;
+pub mod merge_summary_prompt;
+pub mod user_analysis_prompt;
+pub mod category_guides;
@@ -0,0 +1,7 @@
+pub const USER_ANALYSIS_PROMPT: &str = r#"REPOSITORY: {{repository_name}}
+
+Analyze this code:
+{{files}}CRITICAL: Use EXACT line numbers from above. If you reference line 562, ensure it exists in the file.
+
+If you cannot analyze the code, respond with: ANALYSIS_SUMMARY:
+Unable to analyze the provided code. Please ensure valid code was submitted."#;
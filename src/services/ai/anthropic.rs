@@ -1,76 +1,357 @@
+use std::collections::HashMap;
 use std::option::Option;
+use std::time::{Duration, SystemTime};
 use reqwest::Client;
+use reqwest::header::RETRY_AFTER;
 use futures::{Stream, StreamExt};
 use std::pin::Pin;
+use std::sync::Arc;
 use async_trait::async_trait;
 use futures::future;
 use crate::enums::ai_provider_error::AiProviderError;
+use crate::enums::ai_provider_kind::AiProviderKind;
+use crate::enums::chat_role::ChatRole;
 use crate::enums::stream_event_data::StreamEventData;
+use crate::enums::anthropic_content_block::AnthropicContentBlock;
+use crate::services::ai::cassette::Cassette;
 use crate::structs::ai::anthropic::anthropic_message::AnthropicMessage;
 use crate::structs::ai::anthropic::anthropic_message_request::AnthropicMessageRequest;
 use crate::structs::ai::anthropic::anthropic_thinking::AnthropicThinking;
+use crate::structs::ai::anthropic::anthropic_tool::AnthropicTool;
+use crate::structs::ai::anthropic::anthropic_token_count_request::AnthropicTokenCountRequest;
+use crate::structs::ai::anthropic::anthropic_token_count_response::AnthropicTokenCountResponse;
+use crate::structs::ai::chat_message::ChatMessage;
+use crate::structs::ai::retry_policy::RetryPolicy;
+use crate::structs::ai::tool_call::ToolCall;
+use crate::structs::ai::tool_definition::ToolDefinition;
+use crate::structs::config::ai_config::AiConfig;
 use crate::structs::stream_item::StreamItem;
 use crate::traits::ai_provider::AiProvider;
 
+type ItemStream = Pin<Box<dyn Stream<Item = Result<StreamItem, AiProviderError>> + Send>>;
+
+struct ResumableStream {
+    provider: AnthropicProvider,
+    url: String,
+    request_body: AnthropicMessageRequest,
+    inner: ItemStream,
+    restarts: u32,
+    finished: bool,
+}
+
+/// A `tool_use` block whose JSON arguments are still being streamed.
+struct PendingToolCall {
+    id: String,
+    name: String,
+    input_json: String,
+}
+
 #[derive(Clone)]
 pub struct AnthropicProvider {
     api_key: String,
     base_url: String,
     client: Client,
     model: String,
+    max_tokens: u32,
+    temperature: Option<f32>,
+    thinking_budget: Option<u32>,
+    prompt_caching: bool,
+    retry_policy: RetryPolicy,
+    cassette: Arc<Cassette>,
 }
 
 impl AnthropicProvider {
-    pub fn new(api_key: String) -> Self {
+    pub fn new(api_key: String, client: Client, ai_config: &AiConfig) -> Self {
+        // Extended thinking only accepts the default temperature of 1.0.
+        let temperature = match (ai_config.thinking, ai_config.temperature) {
+            (true, _) => Some(1.0),
+            (false, temperature) => temperature,
+        };
+
         Self {
             api_key,
-            base_url: "https://api.anthropic.com/v1".to_string(),
-            client: Client::new(),
-            model: "claude-sonnet-4-20250514".to_string(),
+            base_url: ai_config.resolved_base_url(),
+            client,
+            model: ai_config.resolved_model(),
+            max_tokens: ai_config.resolved_max_tokens(),
+            temperature,
+            thinking_budget: ai_config.thinking.then_some(ai_config.thinking_budget),
+            prompt_caching: ai_config.prompt_caching,
+            retry_policy: RetryPolicy::from_config(ai_config),
+            cassette: Arc::new(Cassette::default()),
         }
     }
 
-    fn get_anthropic_messages(&self, user_prompts: Vec<String>) -> Vec<AnthropicMessage> {
-        user_prompts
-            .iter()
-            .map(|msg| AnthropicMessage {
-                role: String::from("user"),
-                content: msg.clone(),
+    /// Routes every request through `cassette`, to record or replay it.
+    pub fn with_cassette(mut self, cassette: Arc<Cassette>) -> Self {
+        self.cassette = cassette;
+        self
+    }
+
+    /// Anthropic takes the system prompt as a separate field, so system turns are
+    /// pulled out of the conversation. Cache breakpoints become `cache_control` markers
+    /// when prompt caching is enabled.
+    fn get_anthropic_messages(&self, messages: Vec<ChatMessage>) -> (Vec<AnthropicContentBlock>, Vec<AnthropicMessage>) {
+        let (system_messages, conversation): (Vec<ChatMessage>, Vec<ChatMessage>) = messages
+            .into_iter()
+            .partition(|msg| msg.role == ChatRole::System);
+
+        let system_blocks = system_messages
+            .into_iter()
+            .map(|msg| AnthropicContentBlock::text(msg.content, self.prompt_caching && msg.cache_breakpoint))
+            .collect();
+
+        let anthropic_messages = conversation
+            .into_iter()
+            .map(|msg| {
+                // Tool results have to lead the user turn that answers the tool calls.
+                let mut content: Vec<AnthropicContentBlock> = msg.tool_results
+                    .into_iter()
+                    .map(|result| AnthropicContentBlock::ToolResult {
+                        tool_use_id: result.tool_call_id,
+                        content: result.content,
+                        is_error: result.is_error,
+                    })
+                    .collect();
+
+                if !msg.content.is_empty() || (content.is_empty() && msg.tool_calls.is_empty()) {
+                    content.push(AnthropicContentBlock::text(msg.content, self.prompt_caching && msg.cache_breakpoint));
+                }
+
+                content.extend(msg.tool_calls.into_iter().map(|call| AnthropicContentBlock::ToolUse {
+                    id: call.id,
+                    name: call.name,
+                    input: call.input,
+                }));
+
+                AnthropicMessage {
+                    role: msg.role.as_str().to_string(),
+                    content,
+                }
             })
-            .collect()
+            .collect();
+
+        (system_blocks, anthropic_messages)
     }
 
-    fn get_request(&self, system_prompt: String, messages: Vec<AnthropicMessage>, stream: bool) -> AnthropicMessageRequest {
+    fn get_request(&self, system_prompt: Vec<AnthropicContentBlock>, messages: Vec<AnthropicMessage>, tools: Option<Vec<ToolDefinition>>, stream: bool) -> AnthropicMessageRequest {
+        // Thinking blocks would have to be sent back with their signatures on every tool
+        // round, so extended thinking is only used for plain text requests.
+        let thinking_budget = match tools {
+            Some(_) => None,
+            None => self.thinking_budget,
+        };
+
         AnthropicMessageRequest {
             model: self.model.clone(),
-            max_tokens: 64000,
-            temperature: Some(1.0),
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
             system: system_prompt,
             messages,
             stream,
-            thinking: AnthropicThinking {
+            thinking: thinking_budget.map(|budget_tokens| AnthropicThinking {
                 r#type: "enabled".to_string(),
-                budget_tokens: 63999,
-            },
+                budget_tokens,
+            }),
+            tools: tools.map(|tools| tools
+                .into_iter()
+                .map(|tool| AnthropicTool {
+                    name: tool.name,
+                    description: tool.description,
+                    input_schema: tool.input_schema,
+                })
+                .collect()),
         }
     }
 
-    async fn make_request(&self, url: String, request_body: AnthropicMessageRequest) -> Result<reqwest::Response, AiProviderError> {
+    async fn make_request(&self, url: &str, request_body: &AnthropicMessageRequest) -> Result<reqwest::Response, AiProviderError> {
         log::info!("📦 Request model: {}", request_body.model);
 
-        self.client
-            .post(&url)
+        let request = self.client
+            .post(url)
             .header("x-api-key", &self.api_key)
             .header("anthropic-version", "2023-06-01")
             .header("Content-Type", "application/json")
             .header("Accept", "text/event-stream") // Important for SSE
-            .json(&request_body)
-            .send()
+            .json(request_body);
+        self.cassette.send(request, AiProviderError::from_reqwest).await
+    }
+
+    /// `retry-after` is either a number of seconds or an HTTP-date. A value that is neither
+    /// is ignored, and the retry waits for the computed backoff alone.
+    fn parse_retry_after(value: &str) -> Option<Duration> {
+        let value = value.trim();
+        if let Ok(seconds) = value.parse::<f64>() {
+            return Duration::try_from_secs_f64(seconds).ok();
+        }
+        let date = httpdate::parse_http_date(value).ok()?;
+        Some(date.duration_since(SystemTime::now()).unwrap_or_default())
+    }
+
+    async fn error_from_response(response: reqwest::Response) -> AiProviderError {
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Self::parse_retry_after);
+        let error_text = response
+            .text()
             .await
-            .map_err(|e| AiProviderError::NetworkError(e.to_string()))
+            .unwrap_or_else(|_| "Unknown error".to_string());
+
+        log::error!("❌ API Error Response: {}", error_text);
+
+        match status.as_u16() {
+            401 => AiProviderError::AuthenticationError(error_text),
+            429 => AiProviderError::RateLimited { message: error_text, retry_after },
+            408 => AiProviderError::Timeout(format!("HTTP {}: {}", status, error_text)),
+            500 | 502 | 503 | 504 | 529 => AiProviderError::Overloaded(format!("HTTP {}: {}", status, error_text)),
+            _ => AiProviderError::ApiError(format!("HTTP {}: {}", status, error_text)),
+        }
+    }
+
+    async fn open_stream(&self, url: &str, request_body: &AnthropicMessageRequest) -> Result<ItemStream, AiProviderError> {
+        let response = self.make_request(url, request_body).await?;
+
+        if !response.status().is_success() {
+            return Err(Self::error_from_response(response).await);
+        }
+
+        // Use scan for stateful stream processing
+        let stream = response
+            .bytes_stream()
+            .scan((String::new(), HashMap::new()), |(buffer, pending_tools), chunk_result| {
+                future::ready(match chunk_result {
+                    Ok(bytes) => {
+                        let chunk_str = String::from_utf8_lossy(&bytes);
+                        buffer.push_str(&chunk_str);
+
+                        let mut items = Vec::new();
+
+                        // Process buffer line by line
+                        while let Some(newline_pos) = buffer.find('\n') {
+                            let line = buffer[..newline_pos].to_string();
+                            buffer.drain(..=newline_pos);
+
+                            if let Some(result) = Self::parse_sse_line(&line, pending_tools) {
+                                items.push(result);
+                            }
+                        }
+
+                        Some(futures::stream::iter(items))
+                    }
+                    Err(e) => {
+                        let error = AiProviderError::NetworkError(format!("Stream error: {}", e));
+                        Some(futures::stream::iter(vec![Err(error)]))
+                    }
+                })
+            })
+            .flatten();
+
+        Ok(Box::pin(stream))
+    }
+
+    async fn open_stream_with_retry(&self, url: &str, request_body: &AnthropicMessageRequest) -> Result<ItemStream, AiProviderError> {
+        let mut attempt = 0;
+
+        loop {
+            match self.open_stream(url, request_body).await {
+                Ok(stream) => return Ok(stream),
+                Err(e) if e.is_retryable() && attempt < self.retry_policy.max_retries => {
+                    let delay = self.retry_policy.delay_for(attempt, e.retry_after());
+                    attempt += 1;
+                    log::warn!("⏳ {} - retrying in {:.1}s (attempt {}/{})",
+                        e, delay.as_secs_f64(), attempt, self.retry_policy.max_retries);
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Wraps the response stream so that a dropped connection or a transient in-stream
+    /// error restarts the whole request; a `StreamItem::restart()` marker tells the consumer
+    /// to discard the partial output it already received.
+    fn resumable(self, url: String, request_body: AnthropicMessageRequest, inner: ItemStream) -> ItemStream {
+        let state = ResumableStream { provider: self, url, request_body, inner, restarts: 0, finished: false };
+
+        Box::pin(futures::stream::unfold(state, |mut state| async move {
+            if state.finished {
+                return None;
+            }
+
+            let error = match state.inner.next().await {
+                Some(Ok(item)) => {
+                    state.finished = item.is_complete;
+                    return Some((Ok(item), state));
+                }
+                Some(Err(e)) => e,
+                None => AiProviderError::NetworkError("Stream ended before the message was complete".to_string()),
+            };
+
+            if !error.is_retryable() || state.restarts >= state.provider.retry_policy.max_retries {
+                state.finished = true;
+                return Some((Err(error), state));
+            }
+
+            let delay = state.provider.retry_policy.delay_for(state.restarts, error.retry_after());
+            state.restarts += 1;
+            log::warn!("🔌 {} - restarting request in {:.1}s (restart {}/{})",
+                error, delay.as_secs_f64(), state.restarts, state.provider.retry_policy.max_retries);
+            tokio::time::sleep(delay).await;
+
+            match state.provider.open_stream_with_retry(&state.url, &state.request_body).await {
+                Ok(inner) => {
+                    state.inner = inner;
+                    Some((Ok(StreamItem::restart()), state))
+                }
+                Err(e) => {
+                    state.finished = true;
+                    Some((Err(e), state))
+                }
+            }
+        }))
+    }
+
+    /// Arguments cut off by the output token limit do not parse; such a call is dropped
+    /// and the adapter asks the model to carry on from there.
+    fn finish_tool_call(pending: PendingToolCall) -> Option<ToolCall> {
+        // A tool without parameters streams no JSON at all.
+        let input_json = match pending.input_json.trim() {
+            "" => "{}",
+            json => json,
+        };
+
+        match serde_json::from_str(input_json) {
+            Ok(input) => Some(ToolCall { id: pending.id, name: pending.name, input }),
+            Err(e) => {
+                log::warn!("⚠️ Discarding incomplete arguments for tool '{}': {}", pending.name, e);
+                None
+            }
+        }
     }
 
-    fn parse_sse_line(line: &str) -> Option<Result<StreamItem, AiProviderError>> {
+    async fn stream_messages(&self, messages: Vec<ChatMessage>, tools: Option<Vec<ToolDefinition>>) -> Result<ItemStream, AiProviderError> {
+        let url = format!("{}/messages", self.base_url);
+        let (system_prompt, anthropic_messages) = self.get_anthropic_messages(messages);
+        let request_body = self.get_request(system_prompt, anthropic_messages, tools, true);
+
+        let stream = self.open_stream_with_retry(&url, &request_body).await?;
+
+        Ok(self.clone().resumable(url, request_body, stream))
+    }
+
+    fn error_from_event(error_type: &str, message: &str) -> AiProviderError {
+        match error_type {
+            "overloaded_error" | "api_error" => AiProviderError::Overloaded(format!("{}: {}", error_type, message)),
+            "rate_limit_error" => AiProviderError::RateLimited { message: message.to_string(), retry_after: None },
+            "timeout_error" => AiProviderError::Timeout(message.to_string()),
+            _ => AiProviderError::ApiError(format!("{}: {}", error_type, message)),
+        }
+    }
+
+    fn parse_sse_line(line: &str, pending_tools: &mut HashMap<usize, PendingToolCall>) -> Option<Result<StreamItem, AiProviderError>> {
         if line.trim().is_empty() || !line.starts_with("data: ") {
             return None;
         }
@@ -89,17 +370,26 @@ impl AnthropicProvider {
             Ok(event_data) => {
                 let item = match event_data {
                     StreamEventData::MessageStart { message } => {
-                        StreamItem::with_tokens(
+                        let mut item = StreamItem::with_tokens(
                             String::new(),
                             Some(message.usage.input_tokens),
                             Some(message.usage.output_tokens),
-                        )
+                        );
+                        item.cache_creation_tokens = message.usage.cache_creation_input_tokens;
+                        item.cache_read_tokens = message.usage.cache_read_input_tokens;
+                        item
                     },
-                    StreamEventData::ContentBlockDelta { delta, .. } => {
-                        if delta.delta_type == "text_delta" {
-                            StreamItem::new(delta.text.unwrap_or_default())
-                        } else {
-                            StreamItem::new(String::new())
+                    StreamEventData::ContentBlockDelta { index, delta } => {
+                        match delta.delta_type.as_str() {
+                            "text_delta" => StreamItem::new(delta.text.unwrap_or_default()),
+                            "thinking_delta" => StreamItem::thinking(delta.thinking.unwrap_or_default()),
+                            "input_json_delta" => {
+                                if let Some(pending) = pending_tools.get_mut(&index) {
+                                    pending.input_json.push_str(&delta.partial_json.unwrap_or_default());
+                                }
+                                StreamItem::new(String::new())
+                            }
+                            _ => StreamItem::new(String::new()),
                         }
                     }
                     StreamEventData::MessageDelta { delta, usage } => {
@@ -113,44 +403,31 @@ impl AnthropicProvider {
                             StreamItem::new(String::new())
                         }
                     }
-                    StreamEventData::ContentBlockStart { .. } => {
-                        StreamItem {
-                            content: String::new(),
-                            input_tokens: None,
-                            output_tokens: None,
-                            is_complete: false,
-                            stop_reason: None,
+                    StreamEventData::ContentBlockStart { index, content_block } => {
+                        if content_block.block_type == "tool_use" {
+                            pending_tools.insert(index, PendingToolCall {
+                                id: content_block.id.unwrap_or_default(),
+                                name: content_block.name.unwrap_or_default(),
+                                input_json: String::new(),
+                            });
                         }
+                        StreamItem::new(String::new())
                     },
-                    StreamEventData::ContentBlockStop { .. } => {
-                        StreamItem {
-                            content: String::new(),
-                            input_tokens: None,
-                            output_tokens: None,
-                            is_complete: false,
-                            stop_reason: None,
+                    StreamEventData::ContentBlockStop { index } => {
+                        match pending_tools.remove(&index).and_then(Self::finish_tool_call) {
+                            Some(tool_call) => StreamItem::tool_call(tool_call),
+                            None => StreamItem::new(String::new()),
                         }
                     },
                     StreamEventData::MessageStop => {
                         StreamItem {
-                            content: String::new(),
-                            input_tokens: None,
-                            output_tokens: None,
                             is_complete: true,
-                            stop_reason: None,
-                        }
-                    },
-                    StreamEventData::Ping => {
-                        StreamItem {
-                            content: String::new(),
-                            input_tokens: None,
-                            output_tokens: None,
-                            is_complete: false,
-                            stop_reason: None,
+                            ..StreamItem::new(String::new())
                         }
                     },
+                    StreamEventData::Ping => StreamItem::new(String::new()),
                     StreamEventData::Error { error } => {
-                        return Some(Err(AiProviderError::ApiError(format!("{}: {}", error.error_type, error.message))));
+                        return Some(Err(Self::error_from_event(&error.error_type, &error.message)));
                     }
                 };
                 Some(Ok(item))
@@ -163,59 +440,83 @@ impl AnthropicProvider {
 #[async_trait]
 impl AiProvider for AnthropicProvider {
 
-    async fn stream_chat(&self, system_prompt: String, user_prompts: Vec<String>) -> Result<Pin<Box<dyn Stream<Item = Result<StreamItem, AiProviderError>> + Send>>, AiProviderError> {
-        let url = format!("{}/messages", self.base_url);
-        let anthropic_messages = self.get_anthropic_messages(user_prompts);
-        let request_body = self.get_request(system_prompt, anthropic_messages, true);
+    fn model(&self) -> &str {
+        &self.model
+    }
 
-        let response = self.make_request(url, request_body).await?;
+    fn kind(&self) -> AiProviderKind {
+        AiProviderKind::Anthropic
+    }
+
+    fn is_billable(&self) -> bool {
+        !self.cassette.is_replay()
+    }
+
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    async fn stream_chat(&self, messages: Vec<ChatMessage>) -> Result<Pin<Box<dyn Stream<Item = Result<StreamItem, AiProviderError>> + Send>>, AiProviderError> {
+        self.stream_messages(messages, None).await
+    }
+
+    async fn stream_chat_with_tools(&self, messages: Vec<ChatMessage>, tools: Vec<ToolDefinition>) -> Result<Pin<Box<dyn Stream<Item = Result<StreamItem, AiProviderError>> + Send>>, AiProviderError> {
+        self.stream_messages(messages, Some(tools)).await
+    }
+
+    async fn count_tokens(&self, messages: Vec<ChatMessage>) -> Result<u32, AiProviderError> {
+        let url = format!("{}/messages/count_tokens", self.base_url);
+        let (system_prompt, anthropic_messages) = self.get_anthropic_messages(messages);
+        let request_body = AnthropicTokenCountRequest {
+            model: self.model.clone(),
+            system: system_prompt,
+            messages: anthropic_messages,
+        };
+
+        let request = self.client
+            .post(&url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .header("Content-Type", "application/json")
+            .json(&request_body);
+        let response = self.cassette.send(request, AiProviderError::from_reqwest).await?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unknown error".to_string());
-
-            log::error!("❌ API Error Response: {}", error_text);
-
-            return Err(match status.as_u16() {
-                401 => AiProviderError::AuthenticationError(error_text),
-                _ => AiProviderError::ApiError(format!("HTTP {}: {}", status, error_text)),
-            });
+            return Err(Self::error_from_response(response).await);
         }
 
-        // Use scan for stateful stream processing
-        let stream = response
-            .bytes_stream()
-            .scan(String::new(), |buffer, chunk_result| {
-                future::ready(match chunk_result {
-                    Ok(bytes) => {
-                        let chunk_str = String::from_utf8_lossy(&bytes);
-                        buffer.push_str(&chunk_str);
+        let count = response
+            .json::<AnthropicTokenCountResponse>()
+            .await
+            .map_err(|e| AiProviderError::SerializationError(format!("Failed to parse token count: {}", e)))?;
 
-                        let mut items = Vec::new();
+        Ok(count.input_tokens as u32)
+    }
+}
 
-                        // Process buffer line by line
-                        while let Some(newline_pos) = buffer.find('\n') {
-                            let line = buffer[..newline_pos].to_string();
-                            buffer.drain(..=newline_pos);
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+    use super::AnthropicProvider;
 
-                            if let Some(result) = Self::parse_sse_line(&line) {
-                                items.push(result);
-                            }
-                        }
+    #[test]
+    fn retry_after_accepts_seconds() {
+        assert_eq!(AnthropicProvider::parse_retry_after("30"), Some(Duration::from_secs(30)));
+        assert_eq!(AnthropicProvider::parse_retry_after(" 1.5 "), Some(Duration::from_millis(1500)));
+        assert_eq!(AnthropicProvider::parse_retry_after("-1"), None);
+    }
 
-                        Some(futures::stream::iter(items))
-                    }
-                    Err(e) => {
-                        let error = AiProviderError::NetworkError(format!("Stream error: {}", e));
-                        Some(futures::stream::iter(vec![Err(error)]))
-                    }
-                })
-            })
-            .flatten();
+    #[test]
+    fn retry_after_accepts_http_dates() {
+        let later = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(120));
+        let delay = AnthropicProvider::parse_retry_after(&later).unwrap();
+        assert!(delay > Duration::from_secs(110) && delay <= Duration::from_secs(120));
 
-        Ok(Box::pin(stream))
+        assert_eq!(AnthropicProvider::parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_ignores_anything_else() {
+        assert_eq!(AnthropicProvider::parse_retry_after("soon"), None);
     }
-}
\ No newline at end of file
+}
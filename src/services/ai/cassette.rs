@@ -0,0 +1,185 @@
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use bytes::Bytes;
+use futures::future::FutureExt;
+use futures::stream::{BoxStream, Stream, StreamExt};
+use reqwest::{Body, Request, RequestBuilder, Response, StatusCode};
+use crate::config::constants::{CASSETTE_DRAIN_TIMEOUT_SECS, sleep_duration_secs};
+use crate::enums::ai_provider_error::AiProviderError;
+use crate::enums::cassette_mode::CassetteMode;
+use crate::structs::config::cassette_config::CassetteConfig;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Records the raw body of every successful provider response, SSE or NDJSON as it came
+/// over the wire, to a file keyed by a hash of the request. On replay the recorded bytes
+/// are served instead of calling the provider and go through the provider's own parsing.
+#[derive(Debug, Default)]
+pub struct Cassette {
+    mode: CassetteMode,
+    dir: PathBuf,
+}
+
+impl Cassette {
+    pub fn new(config: &CassetteConfig) -> Self {
+        Self { mode: config.mode, dir: config.resolved_dir() }
+    }
+
+    pub fn is_replay(&self) -> bool {
+        self.mode == CassetteMode::Replay
+    }
+
+    /// Sends the request, records or replays it. `network_error` maps transport errors the
+    /// way the provider reports them.
+    pub async fn send<F>(&self, request: RequestBuilder, network_error: F) -> Result<Response, AiProviderError>
+    where
+        F: Fn(reqwest::Error) -> AiProviderError,
+    {
+        if self.mode == CassetteMode::Off {
+            return request.send().await.map_err(network_error);
+        }
+
+        let (client, request) = request.build_split();
+        let request = request.map_err(&network_error)?;
+        let key = Self::cassette_key(&request);
+        let path = self.dir.join(format!("{}.stream", key));
+
+        if self.is_replay() {
+            log::info!("📼 Replaying cassette {}", key);
+            return Ok(Self::response(StatusCode::OK, Body::from(Self::load_cassette(&path)?)));
+        }
+
+        let response = client.execute(request).await.map_err(&network_error)?;
+        if !response.status().is_success() {
+            return Ok(response);
+        }
+
+        Ok(Self::record(response, path, key))
+    }
+
+    /// Forwards the body as it arrives and copies it aside for the cassette.
+    fn record(response: Response, path: PathBuf, key: String) -> Response {
+        let status = response.status();
+        let recording = Recording { chunks: Some(response.bytes_stream().boxed()), path, key, body: Vec::new(), done: false };
+        Self::response(status, Body::wrap_stream(recording))
+    }
+
+    /// FNV-1a of the URL path and the request body, stable across Rust versions and
+    /// platforms unlike `DefaultHasher`. The host is left out so that a cassette recorded
+    /// through a gateway replays for the provider's own endpoint, and the headers so that
+    /// API keys never matter.
+    fn cassette_key(request: &Request) -> String {
+        let path = request.url().path().as_bytes();
+        let body = request.body().and_then(|body| body.as_bytes()).unwrap_or_default();
+
+        let mut hash = FNV_OFFSET_BASIS;
+        for byte in path.iter().chain(std::iter::once(&0u8)).chain(body) {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+        format!("{:016x}", hash)
+    }
+
+    fn response(status: StatusCode, body: Body) -> Response {
+        let mut response = http::Response::new(body);
+        *response.status_mut() = status;
+        Response::from(response)
+    }
+
+    fn load_cassette(path: &Path) -> Result<Vec<u8>, AiProviderError> {
+        std::fs::read(path).map_err(|e| AiProviderError::ApiError(format!(
+            "No cassette recorded for this request at {} ({}); record it first with cassette mode \"record\"",
+            path.display(), e
+        )))
+    }
+
+    fn save_cassette(path: &Path, body: &[u8]) -> Result<(), AiProviderError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| AiProviderError::ApiError(e.to_string()))?;
+        }
+        std::fs::write(path, body).map_err(|e| AiProviderError::ApiError(e.to_string()))
+    }
+}
+
+/// A response body being recorded. The cassette is written once the body has been read to
+/// the end, so a response that fails half-way leaves no recording behind. Providers stop
+/// reading at their own end-of-message event; the rest of the body is then drained, briefly,
+/// before the recording is given up.
+struct Recording {
+    chunks: Option<BoxStream<'static, reqwest::Result<Bytes>>>,
+    path: PathBuf,
+    key: String,
+    body: Vec<u8>,
+    done: bool,
+}
+
+impl Recording {
+    fn observe(&mut self, chunk: Option<&reqwest::Result<Bytes>>) {
+        if self.done {
+            return;
+        }
+        match chunk {
+            Some(Ok(chunk)) => self.body.extend_from_slice(chunk),
+            Some(Err(e)) => {
+                log::warn!("⚠️ Not recording cassette {}: {}", self.key, e);
+                self.done = true;
+            }
+            None => {
+                self.done = true;
+                match Cassette::save_cassette(&self.path, &self.body) {
+                    Ok(()) => log::info!("📼 Recorded cassette {}", self.key),
+                    Err(e) => log::warn!("⚠️ Could not record cassette {}: {}", self.key, e),
+                }
+            }
+        }
+    }
+}
+
+impl Stream for Recording {
+    type Item = reqwest::Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let Some(chunks) = self.chunks.as_mut() else {
+            return Poll::Ready(None);
+        };
+        let chunk = futures::ready!(chunks.poll_next_unpin(cx));
+        self.observe(chunk.as_ref());
+        Poll::Ready(chunk)
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        let Some(mut chunks) = self.chunks.take() else {
+            return;
+        };
+        // Usually the end of the body has already arrived with the last event.
+        while !self.done {
+            match chunks.next().now_or_never() {
+                Some(chunk) => self.observe(chunk.as_ref()),
+                None => break,
+            }
+        }
+        if self.done {
+            return;
+        }
+
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let mut rest = Recording { chunks: None, path: std::mem::take(&mut self.path), key: std::mem::take(&mut self.key), body: std::mem::take(&mut self.body), done: false };
+        runtime.spawn(async move {
+            let drained = tokio::time::timeout(sleep_duration_secs(CASSETTE_DRAIN_TIMEOUT_SECS), async {
+                while !rest.done {
+                    let chunk = chunks.next().await;
+                    rest.observe(chunk.as_ref());
+                }
+            }).await;
+            if drained.is_err() {
+                log::warn!("⚠️ Not recording cassette {}: the response was not read to the end", rest.key);
+            }
+        });
+    }
+}
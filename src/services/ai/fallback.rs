@@ -0,0 +1,177 @@
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use crate::enums::ai_provider_error::AiProviderError;
+use crate::enums::ai_provider_kind::AiProviderKind;
+use crate::structs::ai::chat_message::ChatMessage;
+use crate::structs::ai::serving_provider::ServingProvider;
+use crate::structs::ai::tool_definition::ToolDefinition;
+use crate::structs::stream_item::StreamItem;
+use crate::traits::ai_provider::AiProvider;
+
+type ItemStream = Pin<Box<dyn Stream<Item = Result<StreamItem, AiProviderError>> + Send>>;
+
+/// Tries an ordered list of providers, moving on to the next one when a provider fails with
+/// a retryable error after exhausting its own retries. Once a fallback has served a request
+/// it keeps serving the following ones, so a provider that is down is not retried every call.
+/// Every streamed item names the provider that produced it, since concurrent requests may be
+/// served by different providers.
+pub struct FallbackProvider {
+    providers: Vec<Arc<dyn AiProvider>>,
+    active: Arc<AtomicUsize>,
+}
+
+struct FallbackState {
+    providers: Vec<Arc<dyn AiProvider>>,
+    active: Arc<AtomicUsize>,
+    messages: Vec<ChatMessage>,
+    tools: Option<Vec<ToolDefinition>>,
+    /// The provider serving this request; `active` is only where the next request starts.
+    index: usize,
+    stream: Option<ItemStream>,
+    /// The provider to switch to before the next item, set after a mid-stream failure.
+    pending: Option<usize>,
+}
+
+impl FallbackProvider {
+    pub fn new(providers: Vec<Arc<dyn AiProvider>>) -> Self {
+        Self { providers, active: Arc::new(AtomicUsize::new(0)) }
+    }
+
+    fn active_provider(&self) -> &Arc<dyn AiProvider> {
+        &self.providers[self.active.load(Ordering::Relaxed)]
+    }
+
+    fn label(provider: &Arc<dyn AiProvider>) -> String {
+        format!("{}/{}", provider.kind(), provider.model())
+    }
+
+    /// The next provider after `index` that can serve the request; tool requests skip
+    /// providers without tool support.
+    fn next_candidate(providers: &[Arc<dyn AiProvider>], index: usize, needs_tools: bool) -> Option<usize> {
+        (index + 1..providers.len()).find(|&i| !needs_tools || providers[i].supports_tools())
+    }
+
+    async fn open(state: &mut FallbackState, start: usize) -> Result<(), AiProviderError> {
+        let needs_tools = state.tools.is_some();
+        let mut index = start;
+        if needs_tools && !state.providers[index].supports_tools() {
+            index = Self::next_candidate(&state.providers, index, true).unwrap_or(index);
+        }
+
+        loop {
+            let provider = &state.providers[index];
+            let opened = match &state.tools {
+                Some(tools) => provider.stream_chat_with_tools(state.messages.clone(), tools.clone()).await,
+                None => provider.stream_chat(state.messages.clone()).await,
+            };
+
+            match opened {
+                Ok(stream) => {
+                    state.active.store(index, Ordering::Relaxed);
+                    state.index = index;
+                    state.stream = Some(stream);
+                    return Ok(());
+                }
+                Err(e) => {
+                    let next = Self::next_candidate(&state.providers, index, needs_tools).filter(|_| e.is_retryable());
+                    let Some(next) = next else {
+                        return Err(e);
+                    };
+                    log::warn!("↪️ {} failed ({}), falling back to {}",
+                        Self::label(provider), e, Self::label(&state.providers[next]));
+                    index = next;
+                }
+            }
+        }
+    }
+
+    async fn stream_with_fallback(&self, messages: Vec<ChatMessage>, tools: Option<Vec<ToolDefinition>>) -> Result<ItemStream, AiProviderError> {
+        let mut state = FallbackState {
+            providers: self.providers.clone(),
+            active: self.active.clone(),
+            messages,
+            tools,
+            index: 0,
+            stream: None,
+            pending: None,
+        };
+        let start = self.active.load(Ordering::Relaxed);
+        Self::open(&mut state, start).await?;
+
+        // A mid-stream failure switches to the next provider; the restart marker makes the
+        // consumer discard what the failed provider had streamed so far, and names that
+        // provider so that the consumer can attribute the usage it reported.
+        let stream = futures::stream::unfold(Some(state), |state| async move {
+            let mut state = state?;
+            if let Some(next) = state.pending.take() {
+                if let Err(e) = Self::open(&mut state, next).await {
+                    return Some((Err(e), None));
+                }
+            }
+            let result = state.stream.as_mut()?.next().await?;
+
+            let error = match result {
+                Ok(mut item) => {
+                    item.served_by.get_or_insert_with(|| ServingProvider::of(state.providers[state.index].as_ref()));
+                    return Some((Ok(item), Some(state)));
+                }
+                Err(e) => e,
+            };
+
+            let index = state.index;
+            let next = Self::next_candidate(&state.providers, index, state.tools.is_some()).filter(|_| error.is_retryable());
+            let Some(next) = next else {
+                return Some((Err(error), None));
+            };
+
+            log::warn!("↪️ {} failed mid-stream ({}), falling back to {}",
+                Self::label(&state.providers[index]), error, Self::label(&state.providers[next]));
+            state.pending = Some(next);
+            let restart = StreamItem {
+                served_by: Some(ServingProvider::of(state.providers[index].as_ref())),
+                ..StreamItem::restart()
+            };
+            Some((Ok(restart), Some(state)))
+        });
+
+        Ok(Box::pin(stream))
+    }
+}
+
+#[async_trait]
+impl AiProvider for FallbackProvider {
+
+    /// The model of the provider that served the latest request; streamed items name the
+    /// provider that actually produced them.
+    fn model(&self) -> &str {
+        self.active_provider().model()
+    }
+
+    fn kind(&self) -> AiProviderKind {
+        self.active_provider().kind()
+    }
+
+    fn is_billable(&self) -> bool {
+        self.active_provider().is_billable()
+    }
+
+    /// Follows the primary provider, which decides the output mode of the run.
+    fn supports_tools(&self) -> bool {
+        self.providers[0].supports_tools()
+    }
+
+    async fn stream_chat(&self, messages: Vec<ChatMessage>) -> Result<ItemStream, AiProviderError> {
+        self.stream_with_fallback(messages, None).await
+    }
+
+    async fn stream_chat_with_tools(&self, messages: Vec<ChatMessage>, tools: Vec<ToolDefinition>) -> Result<ItemStream, AiProviderError> {
+        self.stream_with_fallback(messages, Some(tools)).await
+    }
+
+    async fn count_tokens(&self, messages: Vec<ChatMessage>) -> Result<u32, AiProviderError> {
+        self.active_provider().count_tokens(messages).await
+    }
+}
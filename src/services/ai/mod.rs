@@ -1 +1,6 @@
 pub mod anthropic;
+pub mod openai;
+pub mod ollama;
+pub mod provider_factory;
+pub mod cassette;
+pub mod fallback;
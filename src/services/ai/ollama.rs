@@ -0,0 +1,180 @@
+use reqwest::Client;
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::Arc;
+use async_trait::async_trait;
+use futures::future;
+use crate::enums::ai_provider_error::AiProviderError;
+use crate::enums::ai_provider_kind::AiProviderKind;
+use crate::services::ai::cassette::Cassette;
+use crate::structs::ai::ollama::ollama_chat_chunk::OllamaChatChunk;
+use crate::structs::ai::ollama::ollama_chat_request::OllamaChatRequest;
+use crate::structs::ai::ollama::ollama_message::OllamaMessage;
+use crate::structs::ai::ollama::ollama_options::OllamaOptions;
+use crate::structs::ai::chat_message::ChatMessage;
+use crate::structs::config::ai_config::AiConfig;
+use crate::structs::stream_item::StreamItem;
+use crate::traits::ai_provider::AiProvider;
+
+/// Streaming client for a local Ollama-style `/api/chat` endpoint, which answers
+/// with newline-delimited JSON instead of SSE and needs no API key.
+#[derive(Clone)]
+pub struct OllamaProvider {
+    base_url: String,
+    client: Client,
+    model: String,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+    context_window: Option<u32>,
+    cassette: Arc<Cassette>,
+}
+
+impl OllamaProvider {
+    pub fn new(client: Client, ai_config: &AiConfig) -> Self {
+        Self {
+            base_url: ai_config.resolved_base_url(),
+            client,
+            model: ai_config.resolved_model(),
+            max_tokens: ai_config.max_tokens,
+            temperature: ai_config.temperature,
+            context_window: ai_config.context_window,
+            cassette: Arc::new(Cassette::default()),
+        }
+    }
+
+    /// Routes every request through `cassette`, to record or replay it.
+    pub fn with_cassette(mut self, cassette: Arc<Cassette>) -> Self {
+        self.cassette = cassette;
+        self
+    }
+
+    fn get_ollama_messages(&self, messages: Vec<ChatMessage>) -> Vec<OllamaMessage> {
+        messages
+            .into_iter()
+            .map(|msg| OllamaMessage {
+                role: msg.role.as_str().to_string(),
+                content: msg.content,
+            })
+            .collect()
+    }
+
+    fn get_request(&self, messages: Vec<OllamaMessage>) -> OllamaChatRequest {
+        OllamaChatRequest {
+            model: self.model.clone(),
+            messages,
+            stream: true,
+            options: OllamaOptions {
+                temperature: self.temperature,
+                num_predict: self.max_tokens,
+                num_ctx: self.context_window,
+            },
+        }
+    }
+
+    async fn make_request(&self, url: String, request_body: OllamaChatRequest) -> Result<reqwest::Response, AiProviderError> {
+        log::info!("📦 Request model: {}", request_body.model);
+
+        let request = self.client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&request_body);
+        self.cassette
+            .send(request, |e| AiProviderError::NetworkError(format!("{} (is the Ollama server running at {}?)", e, self.base_url)))
+            .await
+    }
+
+    fn parse_ndjson_line(line: &str) -> Option<Result<StreamItem, AiProviderError>> {
+        let line = line.trim();
+        if line.is_empty() {
+            return None;
+        }
+
+        match serde_json::from_str::<OllamaChatChunk>(line) {
+            Ok(chunk) => {
+                if let Some(error) = chunk.error {
+                    return Some(Err(AiProviderError::ApiError(error)));
+                }
+
+                let content = chunk.message.map(|m| m.content).unwrap_or_default();
+
+                if chunk.done {
+                    return Some(Ok(StreamItem {
+                        is_complete: true,
+                        stop_reason: chunk.done_reason,
+                        ..StreamItem::with_tokens(content, chunk.prompt_eval_count, chunk.eval_count)
+                    }));
+                }
+
+                Some(Ok(StreamItem::new(content)))
+            }
+            Err(e) => Some(Err(AiProviderError::SerializationError(format!("Failed to parse chunk: {}", e))))
+        }
+    }
+}
+
+#[async_trait]
+impl AiProvider for OllamaProvider {
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn kind(&self) -> AiProviderKind {
+        AiProviderKind::Ollama
+    }
+
+    fn is_billable(&self) -> bool {
+        !self.cassette.is_replay()
+    }
+
+    async fn stream_chat(&self, messages: Vec<ChatMessage>) -> Result<Pin<Box<dyn Stream<Item = Result<StreamItem, AiProviderError>> + Send>>, AiProviderError> {
+        let url = format!("{}/api/chat", self.base_url);
+        let ollama_messages = self.get_ollama_messages(messages);
+        let request_body = self.get_request(ollama_messages);
+
+        let response = self.make_request(url, request_body).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+
+            log::error!("❌ API Error Response: {}", error_text);
+
+            return Err(AiProviderError::ApiError(format!("HTTP {}: {}", status, error_text)));
+        }
+
+        let stream = response
+            .bytes_stream()
+            .scan(String::new(), |buffer, chunk_result| {
+                future::ready(match chunk_result {
+                    Ok(bytes) => {
+                        let chunk_str = String::from_utf8_lossy(&bytes);
+                        buffer.push_str(&chunk_str);
+
+                        let mut items = Vec::new();
+
+                        while let Some(newline_pos) = buffer.find('\n') {
+                            let line = buffer[..newline_pos].to_string();
+                            buffer.drain(..=newline_pos);
+
+                            if let Some(result) = Self::parse_ndjson_line(&line) {
+                                items.push(result);
+                            }
+                        }
+
+                        Some(futures::stream::iter(items))
+                    }
+                    Err(e) => {
+                        let error = AiProviderError::NetworkError(format!("Stream error: {}", e));
+                        Some(futures::stream::iter(vec![Err(error)]))
+                    }
+                })
+            })
+            .flatten();
+
+        Ok(Box::pin(stream))
+    }
+}
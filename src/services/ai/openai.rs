@@ -0,0 +1,271 @@
+use reqwest::Client;
+use futures::{Stream, StreamExt};
+use std::pin::Pin;
+use std::sync::Arc;
+use async_trait::async_trait;
+use futures::future;
+use crate::enums::ai_provider_error::AiProviderError;
+use crate::enums::ai_provider_kind::AiProviderKind;
+use crate::services::ai::cassette::Cassette;
+use crate::structs::ai::openai::openai_error_response::OpenAIErrorResponse;
+use crate::structs::ai::openai::openai_message::OpenAIMessage;
+use crate::structs::ai::openai::openai_request::OpenAIRequest;
+use crate::structs::ai::openai::openai_stream_chunk::OpenAIStreamChunk;
+use crate::structs::ai::openai::openai_stream_options::OpenAIStreamOptions;
+use crate::structs::ai::chat_message::ChatMessage;
+use crate::structs::config::ai_config::AiConfig;
+use crate::structs::stream_item::StreamItem;
+use crate::traits::ai_provider::AiProvider;
+
+/// Streaming client for the OpenAI Chat Completions API and compatible servers
+/// (vLLM, LM Studio, internal gateways) reachable through `base_url`.
+#[derive(Clone)]
+pub struct OpenAIProvider {
+    api_key: Option<String>,
+    base_url: String,
+    client: Client,
+    model: String,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+    cassette: Arc<Cassette>,
+}
+
+impl OpenAIProvider {
+    pub fn new(api_key: Option<String>, client: Client, ai_config: &AiConfig) -> Self {
+        Self {
+            api_key,
+            base_url: ai_config.resolved_base_url(),
+            client,
+            model: ai_config.resolved_model(),
+            max_tokens: ai_config.max_tokens,
+            temperature: ai_config.temperature,
+            cassette: Arc::new(Cassette::default()),
+        }
+    }
+
+    /// Routes every request through `cassette`, to record or replay it.
+    pub fn with_cassette(mut self, cassette: Arc<Cassette>) -> Self {
+        self.cassette = cassette;
+        self
+    }
+
+    fn get_openai_messages(&self, messages: Vec<ChatMessage>) -> Vec<OpenAIMessage> {
+        messages
+            .into_iter()
+            .map(|msg| OpenAIMessage {
+                role: msg.role.as_str().to_string(),
+                content: msg.content,
+            })
+            .collect()
+    }
+
+    fn get_request(&self, messages: Vec<OpenAIMessage>) -> OpenAIRequest {
+        OpenAIRequest {
+            model: self.model.clone(),
+            messages,
+            max_tokens: self.max_tokens,
+            temperature: self.temperature,
+            stream: true,
+            stream_options: Some(OpenAIStreamOptions { include_usage: true }),
+            top_p: None,
+            frequency_penalty: None,
+            presence_penalty: None,
+        }
+    }
+
+    async fn make_request(&self, url: String, request_body: OpenAIRequest) -> Result<reqwest::Response, AiProviderError> {
+        log::info!("📦 Request model: {}", request_body.model);
+
+        let mut request = self.client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .header("Accept", "text/event-stream");
+
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        self.cassette
+            .send(request.json(&request_body), |e| AiProviderError::NetworkError(e.to_string()))
+            .await
+    }
+
+    /// Splits the response body into SSE lines; a line cut across two reads is parsed once
+    /// its newline arrives.
+    fn parse_sse_stream<B: AsRef<[u8]>, E: std::fmt::Display>(
+        bytes: impl Stream<Item = Result<B, E>>,
+    ) -> impl Stream<Item = Result<StreamItem, AiProviderError>> {
+        bytes
+            .scan(String::new(), |buffer, chunk_result| {
+                future::ready(match chunk_result {
+                    Ok(bytes) => {
+                        let chunk_str = String::from_utf8_lossy(bytes.as_ref());
+                        buffer.push_str(&chunk_str);
+
+                        let mut items = Vec::new();
+
+                        while let Some(newline_pos) = buffer.find('\n') {
+                            let line = buffer[..newline_pos].to_string();
+                            buffer.drain(..=newline_pos);
+
+                            if let Some(result) = Self::parse_sse_line(&line) {
+                                items.push(result);
+                            }
+                        }
+
+                        Some(futures::stream::iter(items))
+                    }
+                    Err(e) => {
+                        let error = AiProviderError::NetworkError(format!("Stream error: {}", e));
+                        Some(futures::stream::iter(vec![Err(error)]))
+                    }
+                })
+            })
+            .flatten()
+    }
+
+    fn parse_sse_line(line: &str) -> Option<Result<StreamItem, AiProviderError>> {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || !line.starts_with("data:") {
+            return None;
+        }
+
+        let data = line["data:".len()..].trim();
+
+        // The terminating sentinel is the only reliable end-of-stream marker: with
+        // `include_usage` the usage chunk arrives after the `finish_reason` chunk.
+        if data == "[DONE]" {
+            return Some(Ok(StreamItem {
+                is_complete: true,
+                ..StreamItem::new(String::new())
+            }));
+        }
+
+        match serde_json::from_str::<OpenAIStreamChunk>(data) {
+            Ok(chunk) => {
+                let mut content = String::new();
+                let mut stop_reason = None;
+
+                for choice in chunk.choices {
+                    if let Some(text) = choice.delta.content {
+                        content.push_str(&text);
+                    }
+                    if choice.finish_reason.is_some() {
+                        stop_reason = choice.finish_reason;
+                    }
+                }
+
+                let (input_tokens, output_tokens) = match chunk.usage {
+                    Some(usage) => (Some(usage.prompt_tokens), Some(usage.completion_tokens)),
+                    None => (None, None),
+                };
+
+                Some(Ok(StreamItem {
+                    stop_reason,
+                    ..StreamItem::with_tokens(content, input_tokens, output_tokens)
+                }))
+            }
+            Err(e) => match serde_json::from_str::<OpenAIErrorResponse>(data) {
+                Ok(error_response) => Some(Err(AiProviderError::ApiError(format!(
+                    "{}: {}",
+                    error_response.error.error_type.unwrap_or_else(|| "error".to_string()),
+                    error_response.error.message
+                )))),
+                Err(_) => Some(Err(AiProviderError::SerializationError(format!("Failed to parse event: {}", e)))),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl AiProvider for OpenAIProvider {
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn kind(&self) -> AiProviderKind {
+        AiProviderKind::OpenAi
+    }
+
+    fn is_billable(&self) -> bool {
+        !self.cassette.is_replay()
+    }
+
+    async fn stream_chat(&self, messages: Vec<ChatMessage>) -> Result<Pin<Box<dyn Stream<Item = Result<StreamItem, AiProviderError>> + Send>>, AiProviderError> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let openai_messages = self.get_openai_messages(messages);
+        let request_body = self.get_request(openai_messages);
+
+        let response = self.make_request(url, request_body).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+
+            log::error!("❌ API Error Response: {}", error_text);
+
+            return Err(match status.as_u16() {
+                401 | 403 => AiProviderError::AuthenticationError(error_text),
+                _ => AiProviderError::ApiError(format!("HTTP {}: {}", status, error_text)),
+            });
+        }
+
+        Ok(Box::pin(Self::parse_sse_stream(response.bytes_stream())))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use super::OpenAIProvider;
+
+    /// Reads of a real response rarely line up with events; these cut through the `data:`
+    /// prefix, a JSON object and a CRLF pair.
+    const SSE_READS: &[&str] = &[
+        "data: {\"choices\":[{\"delta\":{\"content\":\"Hel\"},\"finish_reason\":null}]}\n\nda",
+        "ta: {\"choices\":[{\"delta\":{\"content\":\"lo\"},\"finish_rea",
+        "son\":\"stop\"}]}\r",
+        "\n\n: keep-alive\n\ndata: {\"choices\":[],\"usage\":{\"prompt_tokens\":12,\"completion_tokens\":3}}\n\n",
+        "data: [DO",
+        "NE]\n\n",
+    ];
+
+    fn parse(reads: &[&'static str]) -> Vec<super::StreamItem> {
+        let bytes = futures::stream::iter(reads.iter().map(|read| Ok::<_, String>(read.as_bytes())));
+        futures::executor::block_on(OpenAIProvider::parse_sse_stream(bytes).collect::<Vec<_>>())
+            .into_iter()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    #[test]
+    fn events_split_across_reads_are_parsed_once_complete() {
+        let items = parse(SSE_READS);
+        let content: String = items.iter().map(|item| item.content.as_str()).collect();
+
+        assert_eq!(items.len(), 4);
+        assert_eq!(content, "Hello");
+        assert_eq!(items[1].stop_reason.as_deref(), Some("stop"));
+    }
+
+    #[test]
+    fn usage_chunk_after_finish_reason_is_kept_and_done_ends_the_stream() {
+        let items = parse(SSE_READS);
+
+        assert!(!items[1].is_complete);
+        assert_eq!((items[2].input_tokens, items[2].output_tokens), (Some(12), Some(3)));
+        assert!(items[3].is_complete);
+    }
+
+    #[test]
+    fn an_incomplete_last_line_is_not_parsed() {
+        let items = parse(&["data: {\"choices\":[{\"delta\":{\"content\":\"Hi\"}}]}\n", "data: [DONE]"]);
+
+        assert_eq!(items.len(), 1);
+        assert!(!items[0].is_complete);
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+use std::time::Duration;
+use reqwest::Client;
+use crate::enums::ai_provider_kind::AiProviderKind;
+use crate::enums::cassette_mode::CassetteMode;
+use crate::errors::{AicedError, AicedResult};
+use crate::services::ai::anthropic::AnthropicProvider;
+use crate::services::ai::cassette::Cassette;
+use crate::services::ai::fallback::FallbackProvider;
+use crate::services::ai::ollama::OllamaProvider;
+use crate::services::ai::openai::OpenAIProvider;
+use crate::services::http_client_factory::HttpClientFactory;
+use crate::structs::config::ai_config::AiConfig;
+use crate::structs::config::cassette_config::CassetteConfig;
+use crate::structs::config::http_config::HttpConfig;
+use crate::traits::ai_provider::AiProvider;
+
+pub struct ProviderFactory;
+
+impl ProviderFactory {
+
+    /// Creates the configured provider with its fallbacks chained behind it. With cassettes
+    /// on, every provider records its raw responses or replays them, in which case neither
+    /// network access nor an API key is needed.
+    pub fn create_with_cassette(ai_config: &AiConfig, cassette_config: &CassetteConfig, http_config: &HttpConfig) -> AicedResult<Arc<dyn AiProvider>> {
+        match cassette_config.mode {
+            CassetteMode::Off => {}
+            CassetteMode::Record => log::info!("📼 Recording cassettes to {}", cassette_config.resolved_dir().display()),
+            CassetteMode::Replay => log::info!("📼 Replaying cassettes from {}", cassette_config.resolved_dir().display()),
+        }
+
+        let cassette = Arc::new(Cassette::new(cassette_config));
+        Self::create_chain(ai_config, |config| Self::create(config, http_config, Arc::clone(&cassette)))
+    }
+
+    fn create_chain<F>(ai_config: &AiConfig, create: F) -> AicedResult<Arc<dyn AiProvider>>
+    where
+        F: Fn(&AiConfig) -> AicedResult<Arc<dyn AiProvider>>,
+    {
+        let primary = create(ai_config)?;
+        if ai_config.fallbacks.is_empty() {
+            return Ok(primary);
+        }
+
+        let mut providers = vec![primary];
+        for fallback in &ai_config.fallbacks {
+            providers.push(create(fallback)?);
+        }
+        Ok(Arc::new(FallbackProvider::new(providers)))
+    }
+
+    pub fn create(ai_config: &AiConfig, http_config: &HttpConfig, cassette: Arc<Cassette>) -> AicedResult<Arc<dyn AiProvider>> {
+        log::info!("🤖 Using AI provider: {} ({})", ai_config.provider, ai_config.resolved_model());
+
+        let client = Self::build_client(ai_config, http_config)?;
+        let replay = cassette.is_replay();
+
+        match ai_config.provider {
+            AiProviderKind::Anthropic => {
+                let api_key = match replay {
+                    true => Self::optional_api_key(ai_config).unwrap_or_default(),
+                    false => Self::required_api_key(ai_config)?,
+                };
+                Ok(Arc::new(AnthropicProvider::new(api_key, client, ai_config).with_cassette(cassette)))
+            }
+            AiProviderKind::OpenAi => {
+                // Self-hosted OpenAI-compatible servers usually run without authentication,
+                // so the key is only mandatory when talking to the default endpoint.
+                let api_key = match ai_config.base_url {
+                    Some(_) => Self::optional_api_key(ai_config),
+                    None if replay => Self::optional_api_key(ai_config),
+                    None => Some(Self::required_api_key(ai_config)?),
+                };
+                Ok(Arc::new(OpenAIProvider::new(api_key, client, ai_config).with_cassette(cassette)))
+            }
+            AiProviderKind::Ollama => {
+                Ok(Arc::new(OllamaProvider::new(client, ai_config).with_cassette(cassette)))
+            }
+        }
+    }
+
+    fn build_client(ai_config: &AiConfig, http_config: &HttpConfig) -> AicedResult<Client> {
+        HttpClientFactory::builder(http_config)?
+            .timeout(Duration::from_secs(ai_config.request_timeout_secs))
+            .build()
+            .map_err(|e| AicedError::system_error("build HTTP client", &e.to_string()))
+    }
+
+    fn required_api_key(ai_config: &AiConfig) -> AicedResult<String> {
+        let env_name = ai_config.resolved_api_key_env()
+            .ok_or_else(|| AicedError::configuration_error(
+                &format!("No API key environment variable configured for provider '{}'", ai_config.provider),
+                Some("ai.api_key_env"),
+                Some("Set ai.api_key_env to the name of the environment variable holding your API key")
+            ))?;
+
+        let api_key = std::env::var(&env_name)
+            .map_err(|_| AicedError::configuration_error(
+                &format!("{} environment variable not set", env_name),
+                Some("environment"),
+                Some(&format!("Set your API key: export {}=your_key_here", env_name))
+            ))?;
+
+        if api_key.trim().is_empty() {
+            return Err(AicedError::configuration_error(
+                &format!("{} cannot be empty", env_name),
+                Some("environment"),
+                Some("Provide a valid API key")
+            ));
+        }
+
+        Ok(api_key)
+    }
+
+    fn optional_api_key(ai_config: &AiConfig) -> Option<String> {
+        ai_config.resolved_api_key_env()
+            .and_then(|env_name| std::env::var(env_name).ok())
+            .filter(|api_key| !api_key.trim().is_empty())
+    }
+}
@@ -1,13 +1,16 @@
 use crate::enums::file_change::FileChange;
 use crate::enums::line_change::LineChange;
+use crate::helpers::analysis_tools::{REPORT_CHANGE_TOOL, REPORT_SUMMARY_TOOL, REPORT_TECHNOLOGY_STACK_TOOL};
+use crate::structs::ai::tool_call::ToolCall;
 use crate::structs::analysis_response::AnalysisResponse;
+use crate::structs::change_filter::ChangeFilter;
 use crate::structs::technology_stack::TechnologyStack;
 use std::collections::HashMap;
 use crate::errors::{AicedError, AicedResult};
 
 const ANALYSIS_SUMMARY_MARKER: &str = "ANALYSIS_SUMMARY:";
-const CHANGE_MARKER: &str = "CHANGE:";
-const END_CHANGE_MARKER: &str = "END_CHANGE";
+pub const CHANGE_MARKER: &str = "CHANGE:";
+pub const END_CHANGE_MARKER: &str = "END_CHANGE";
 const FILE_FIELD: &str = "FILE:";
 const REASON_FIELD: &str = "REASON:";
 const SEVERITY_FIELD: &str = "SEVERITY:";
@@ -65,7 +68,8 @@ impl AnalysisParser {
         let mut response = AnalysisResponse {
             technology_stack: None,
             analysis_summary: String::new(),
-            changes: Vec::new()
+            changes: Vec::new(),
+            thinking: String::new(),
         };
 
         if self.has_technology_stack() {
@@ -97,6 +101,108 @@ impl AnalysisParser {
         Ok(response)
     }
 
+    /// Builds the response from the calls made in the structured output mode.
+    /// Calls are expected to have passed `validate_tool_call` already.
+    pub fn from_tool_calls(tool_calls: &[ToolCall]) -> AicedResult<AnalysisResponse> {
+        let response = Self::from_partial_tool_calls(tool_calls);
+
+        if response.analysis_summary.is_empty() {
+            return Err(AicedError::parse_error("ParseError", None, "ParseError", Some("Analysis summary was not reported")));
+        }
+
+        Ok(response)
+    }
+
+    /// Like `from_tool_calls`, but accepts a response that never reported its summary.
+    pub fn from_partial_tool_calls(tool_calls: &[ToolCall]) -> AnalysisResponse {
+        let mut response = AnalysisResponse {
+            technology_stack: None,
+            analysis_summary: String::new(),
+            changes: Vec::new(),
+            thinking: String::new(),
+        };
+
+        for tool_call in tool_calls {
+            let result = match tool_call.name.as_str() {
+                REPORT_CHANGE_TOOL => Self::change_from_tool_call(tool_call).map(|change| response.changes.push(change)),
+                REPORT_TECHNOLOGY_STACK_TOOL => Self::technology_stack_from_tool_call(tool_call)
+                    .map(|stack| response.technology_stack = Some(stack)),
+                REPORT_SUMMARY_TOOL => Self::summary_from_tool_call(tool_call).map(|summary| {
+                    if !response.analysis_summary.is_empty() {
+                        response.analysis_summary.push_str("\n\n");
+                    }
+                    response.analysis_summary.push_str(&summary);
+                }),
+                other => Err(format!("unknown tool '{}'", other)),
+            };
+
+            if let Err(e) = result {
+                log::error!("❌ Error reading {} call {}: {}", tool_call.name, tool_call.id, e);
+            }
+        }
+
+        response
+    }
+
+    /// Drops changes the filter does not accept: other categories than the selected ones,
+    /// or files that are only context.
+    pub fn retain_allowed(response: &mut AnalysisResponse, filter: &ChangeFilter) {
+        response.changes.retain(|change| match filter.check(change) {
+            Ok(()) => true,
+            Err(e) => {
+                log::warn!("⚠️ Dropped change to {}: {}", change.get_file_path(), e);
+                false
+            }
+        });
+    }
+
+    /// Checks a single tool call so that a malformed one, or a change the filter rejects,
+    /// can be sent back to the model.
+    pub fn validate_tool_call(tool_call: &ToolCall, filter: &ChangeFilter) -> Result<(), String> {
+        match tool_call.name.as_str() {
+            REPORT_CHANGE_TOOL => Self::change_from_tool_call(tool_call).and_then(|change| filter.check(&change)),
+            REPORT_TECHNOLOGY_STACK_TOOL => Self::technology_stack_from_tool_call(tool_call).map(|_| ()),
+            REPORT_SUMMARY_TOOL => Self::summary_from_tool_call(tool_call).map(|_| ()),
+            other => Err(format!("unknown tool '{}'", other)),
+        }
+    }
+
+    fn change_from_tool_call(tool_call: &ToolCall) -> Result<FileChange, String> {
+        let change = serde_json::from_value::<FileChange>(tool_call.input.clone())
+            .map_err(|e| format!("invalid change: {}", e))?;
+
+        match &change {
+            FileChange::ModifyFile { line_changes, .. } => {
+                if line_changes.is_empty() {
+                    return Err("modify_file needs at least one line change".to_string());
+                }
+                for line_change in line_changes {
+                    line_change.validate().map_err(|e| format!("{}: {}", line_change.get_description(), e))?;
+                }
+            }
+            FileChange::CreateFile { content, .. } if content.trim().is_empty() => {
+                return Err("create_file needs the complete file content".to_string());
+            }
+            _ => {}
+        }
+
+        Ok(change)
+    }
+
+    fn technology_stack_from_tool_call(tool_call: &ToolCall) -> Result<TechnologyStack, String> {
+        serde_json::from_value::<TechnologyStack>(tool_call.input.clone())
+            .map_err(|e| format!("invalid technology stack: {}", e))
+    }
+
+    fn summary_from_tool_call(tool_call: &ToolCall) -> Result<String, String> {
+        tool_call.input
+            .get("summary")
+            .and_then(|summary| summary.as_str())
+            .map(|summary| summary.trim().to_string())
+            .filter(|summary| !summary.is_empty())
+            .ok_or_else(|| "summary must be a non-empty string".to_string())
+    }
+
     fn has_technology_stack(&self) -> bool {
         self.lines.iter().any(|line| line.trim().starts_with(TECHNOLOGY_STACK_MARKER))
     }
@@ -307,6 +413,7 @@ impl AnalysisParser {
             reason: self.get_required_field(&fields, REASON_FIELD)?,
             severity: self.get_required_field(&fields, SEVERITY_FIELD)?,
             category: self.get_required_field(&fields, CATEGORY_FIELD)?,
+            rationale: None,
             line_changes,
         })
     }
@@ -336,6 +443,7 @@ impl AnalysisParser {
             reason: self.get_required_field(&fields, REASON_FIELD)?,
             severity: self.get_required_field(&fields, SEVERITY_FIELD)?,
             category: self.get_required_field(&fields, CATEGORY_FIELD)?,
+            rationale: None,
             content,
         })
     }
@@ -397,6 +505,7 @@ impl AnalysisParser {
             reason: self.get_required_field(&fields, REASON_FIELD)?,
             severity: self.get_required_field(&fields, SEVERITY_FIELD)?,
             category: self.get_required_field(&fields, CATEGORY_FIELD)?,
+            rationale: None,
         })
     }
 
@@ -0,0 +1,192 @@
+use std::path::Path;
+use std::sync::Arc;
+use crate::adapters::aiced_adapter::AicedAdapter;
+use crate::config::constants::ANALYSIS_CATEGORIES;
+use crate::enums::ai_phase::AiPhase;
+use crate::enums::chunk_strategy::ChunkStrategy;
+use crate::enums::file_selection::FileSelection;
+use crate::enums::output_mode::OutputMode;
+use crate::enums::oversize_policy::OversizePolicy;
+use crate::enums::skip_reason::SkipReason;
+use crate::errors::{AicedError, AicedResult};
+use crate::helpers::{chunking, project_context, token_estimator};
+use crate::services::prompt_builder::PromptBuilder;
+use crate::services::repo_scanner::RepoScanner;
+use crate::structs::change_filter::ChangeFilter;
+use crate::structs::config::ai_config::AiConfig;
+use crate::structs::config::analysis_config::AnalysisConfig;
+use crate::structs::config::config::Config;
+use crate::structs::config::repository_config::RepositoryConfig;
+use crate::structs::file_info::FileInfo;
+use crate::structs::file_rules::FileRules;
+use crate::structs::scan_report::ScanReport;
+
+/// Everything an analysis sends, short of the model: the prompts, the scanned files and their
+/// batches. It builds no AI provider, so prompts can be rendered without credentials.
+pub struct AnalysisPlanner {
+    pub repository_config: Arc<RepositoryConfig>,
+    pub ai_config: AiConfig,
+    pub analysis_config: AnalysisConfig,
+    pub use_tools: bool,
+    pub prompt_builder: PromptBuilder,
+    pub change_filter: ChangeFilter,
+    repo_scanner: RepoScanner,
+}
+
+impl AnalysisPlanner {
+
+    /// `since` limits the analysis to files changed since that git ref.
+    pub fn new(repository_config: Arc<RepositoryConfig>, config: &Config, since: Option<String>) -> AicedResult<Self> {
+        let ai_config = config.ai_config_for(&repository_config).for_phase(AiPhase::Analysis).clone();
+        let use_tools = match ai_config.output_mode {
+            OutputMode::Tools if ai_config.provider.supports_tools() => true,
+            OutputMode::Tools => {
+                log::warn!("⚠️ Provider '{}' has no tool support, falling back to the text output protocol", ai_config.provider);
+                false
+            }
+            OutputMode::Text => false,
+        };
+        let analysis_config = config.analysis_config_for(&repository_config);
+        let categories = analysis_config.selected_categories();
+        if categories.len() < ANALYSIS_CATEGORIES.len() {
+            log::info!("🏷️ Analyzing categories: {}", categories.join(", "));
+        }
+        if analysis_config.file_selection != FileSelection::Ai {
+            log::info!("🧮 Selecting files by {}", analysis_config.file_selection);
+        }
+        let project_context = project_context::collect(&repository_config.path);
+        if !project_context.is_empty() {
+            log::info!("📎 Sending {} manifests and configs as read-only project context", project_context.len());
+        }
+        let mut change_filter = ChangeFilter::new(categories);
+        change_filter.protect(project_context.iter().map(|file| &file.path));
+        let prompt_builder = PromptBuilder::new(&config.prompt_config_for(&repository_config), &analysis_config, &repository_config, use_tools)?
+            .with_context(project_context);
+        let mut repo_scanner = RepoScanner::new(Arc::clone(&repository_config))
+            .with_limits(analysis_config.max_file_size_bytes(), analysis_config.max_files)
+            .with_file_selection(analysis_config.file_selection, FileRules::from_config(&analysis_config));
+        if let Some(since) = since {
+            repo_scanner = repo_scanner.changed_since(since, analysis_config.include_unchanged_neighbours);
+        }
+        Ok(Self {
+            repository_config,
+            ai_config,
+            analysis_config,
+            use_tools,
+            prompt_builder,
+            change_filter,
+            repo_scanner,
+        })
+    }
+
+    /// The adapter for the file filter; without one a stale filter cache fails the scan.
+    pub fn with_adapter(mut self, adapter: Arc<AicedAdapter>) -> Self {
+        self.repo_scanner = self.repo_scanner.with_adapter(adapter);
+        self
+    }
+
+    pub fn system_prompt(&self) -> &str {
+        self.prompt_builder.system_prompt()
+    }
+
+    /// The user prompts the analysis would send, one per batch. The file filter is never
+    /// called: a stale or missing filter cache is replaced by the selection rules.
+    pub async fn render_user_prompts(&self) -> AicedResult<Vec<String>> {
+        let (files, mut report) = self.repo_scanner.preview_files().await?;
+        let files = self.protect(files);
+        if files.iter().all(|file| file.read_only) {
+            report.log(files.len());
+            return Ok(Vec::new());
+        }
+
+        let prompts = match self.analysis_config.chunk_strategy {
+            ChunkStrategy::Single => {
+                report.log(files.len());
+                vec![self.prompt_builder.user_prompt(&files)]
+            }
+            strategy => self.plan_batches(&files, strategy, &mut report)?
+                .iter()
+                .map(|batch| self.prompt_builder.user_prompt(batch))
+                .collect(),
+        };
+        Ok(prompts)
+    }
+
+    /// Scanned files without the project context files, which are sent read-only anyway.
+    pub async fn scan_files(&self) -> AicedResult<(Vec<FileInfo>, ScanReport)> {
+        let (files, report) = self.repo_scanner.scan_files().await?;
+        Ok((self.protect(files), report))
+    }
+
+    /// Drops the files the run's filter protects.
+    fn protect(&self, mut files: Vec<FileInfo>) -> Vec<FileInfo> {
+        let repo_path = &self.repository_config.path;
+        files.retain(|file| !self.change_filter.is_protected(&file.path.replace(repo_path, "")));
+        files
+    }
+
+    /// The run's filter plus the read-only neighbours of this scan.
+    pub fn filter_for(&self, files: &[FileInfo]) -> ChangeFilter {
+        let repo_path = &self.repository_config.path;
+        let mut filter = self.change_filter.clone();
+        filter.protect(files.iter().filter(|file| file.read_only).map(|file| file.path.replace(repo_path, "")));
+        filter
+    }
+
+    /// The batches to send, with files larger than a whole batch handled by the
+    /// `oversize_policy`: refused, sent alone with a warning, or skipped since a single file
+    /// cannot be split any further. Skipped files go into `report`, which is logged here.
+    pub fn plan_batches(&self, files: &[FileInfo], strategy: ChunkStrategy, report: &mut ScanReport) -> AicedResult<Vec<Vec<FileInfo>>> {
+        let repo_path = &self.repository_config.path;
+        let budget = self.batch_token_budget();
+        let plan = chunking::split_into_batches(files, repo_path, strategy, budget);
+        let mut batches = plan.batches;
+
+        for (file, tokens) in plan.oversized {
+            let path = file.path.replace(repo_path, "");
+            match self.ai_config.oversize_policy {
+                OversizePolicy::Refuse => {
+                    return Err(AicedError::AnalysisError {
+                        repository: self.repository_config.name.clone(),
+                        stage: "preflight".to_string(),
+                        reason: format!(
+                            "{} needs {} tokens but a batch holds {}; exclude the file or set oversize_policy = \"split\"",
+                            path, tokens, budget
+                        ),
+                        recoverable: false,
+                    });
+                }
+                OversizePolicy::Warn => {
+                    log::warn!("⚠️ {} ({} tokens) exceeds the batch budget ({}), sending it alone", path, tokens, budget);
+                    batches.push(vec![file]);
+                }
+                OversizePolicy::Split => {
+                    report.skip(Path::new(&file.path), repo_path, SkipReason::TooManyTokens { tokens, budget });
+                }
+            }
+        }
+
+        report.log(batches.iter().map(Vec::len).sum());
+
+        if batches.is_empty() {
+            return Err(AicedError::AnalysisError {
+                repository: self.repository_config.name.clone(),
+                stage: "preflight".to_string(),
+                reason: format!("no file fits into the batch budget of {} tokens", budget),
+                recoverable: false,
+            });
+        }
+        Ok(batches)
+    }
+
+    /// Estimated tokens available for the files of one batch, after the system prompt and
+    /// the fixed part of the user prompt.
+    fn batch_token_budget(&self) -> u32 {
+        let budget = self.ai_config.input_token_budget();
+        let budget = self.analysis_config.max_batch_tokens.map_or(budget, |max| max.min(budget));
+        let overhead = token_estimator::estimate_tokens(self.prompt_builder.system_prompt())
+            + token_estimator::estimate_tokens(&self.prompt_builder.user_prompt(&[]));
+        budget.saturating_sub(overhead)
+    }
+
+}
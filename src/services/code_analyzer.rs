@@ -1,65 +1,281 @@
 use std::rc::Rc;
 use std::sync::Arc;
+use futures::StreamExt;
+use tokio_util::sync::CancellationToken;
 use crate::adapters::aiced_adapter::AicedAdapter;
-use crate::config::constants::ANTHROPIC_API_KEY_ENV;
+use crate::enums::ai_phase::AiPhase;
+use crate::enums::chunk_strategy::ChunkStrategy;
+use crate::enums::oversize_policy::OversizePolicy;
 use crate::errors::{AicedError, AicedResult};
-use crate::helpers::prompt_generator;
+use crate::helpers::{analysis_tools, git_helper};
 use crate::logger::animated_logger::AnimatedLogger;
-use crate::prompts::system_analysis_prompt::SYSTEM_ANALYSIS_PROMPT;
-use crate::services::ai::anthropic::AnthropicProvider;
-use crate::services::analysis_parser::AnalysisParser;
-use crate::services::repo_scanner::RepoScanner;
+use crate::prompts::merge_summary_prompt::MERGE_SUMMARY_PROMPT;
+use crate::services::ai::provider_factory::ProviderFactory;
+use crate::services::analysis_planner::AnalysisPlanner;
+use crate::services::analysis_parser::{AnalysisParser, END_CHANGE_MARKER};
+use crate::services::usage_tracker::UsageTracker;
+use crate::structs::analysis_response::AnalysisResponse;
 use crate::structs::analyze_repository_response::AnalyzeRepositoryResponse;
+use crate::structs::config::config::Config;
 use crate::structs::config::repository_config::RepositoryConfig;
+use crate::structs::file_info::FileInfo;
+use crate::structs::scan_report::ScanReport;
+use crate::structs::stream_result::StreamResult;
 
 pub struct CodeAnalyzer {
-    repo_scanner: RepoScanner,
-    repository_config: Arc<RepositoryConfig>,
+    planner: AnalysisPlanner,
     adapter: Arc<AicedAdapter>,
+    cancellation: CancellationToken,
 }
 
 impl CodeAnalyzer {
 
-    pub fn new(repository_config: Arc<RepositoryConfig>) -> AicedResult<Self> {
-        let api_key = std::env::var(ANTHROPIC_API_KEY_ENV)
-            .map_err(|_| AicedError::configuration_error(
-                "ANTHROPIC_API_KEY environment variable not set",
-                Some("environment"),
-                Some("Set your Anthropic API key: export ANTHROPIC_API_KEY=your_key_here")
-            ))?;
-        
-        if api_key.trim().is_empty() {
-            return Err(AicedError::configuration_error(
-                "ANTHROPIC_API_KEY cannot be empty",
-                Some("environment"),
-                Some("Provide a valid Anthropic API key")
-            ));
-        }
-
-        let ai_provider = Arc::new(AnthropicProvider::new(api_key));
-        let adapter = Arc::new(AicedAdapter::new(ai_provider));
-        Ok(Self { 
-            repo_scanner: RepoScanner::new(Arc::clone(&repository_config), Arc::clone(&adapter)), 
-            repository_config, 
-            adapter 
-        })
+    /// `since` limits the analysis to files changed since that git ref.
+    pub fn new(repository_config: Arc<RepositoryConfig>, config: &Config, usage_tracker: Arc<UsageTracker>, cancellation: CancellationToken, since: Option<String>) -> AicedResult<Self> {
+        let repo_ai_config = config.ai_config_for(&repository_config);
+        let provider = ProviderFactory::create_with_cassette(&repo_ai_config, &config.cassette, &config.http)?;
+        let mut adapter = AicedAdapter::new(provider, usage_tracker, repository_config.name.clone(), cancellation.clone());
+        for phase in repo_ai_config.phases.keys() {
+            log::info!("🔀 Routing {} phase to its own provider", phase);
+            adapter.set_phase_provider(*phase, ProviderFactory::create_with_cassette(repo_ai_config.for_phase(*phase), &config.cassette, &config.http)?);
+        }
+        let adapter = Arc::new(adapter);
+        let planner = AnalysisPlanner::new(repository_config, config, since)?
+            .with_adapter(Arc::clone(&adapter));
+        Ok(Self { planner, adapter, cancellation })
     }
 
     pub async fn analyze_repository(&self) -> AicedResult<Rc<AnalyzeRepositoryResponse>> {
-        let files = self.repo_scanner.scan_files().await?;
-        let user_prompt = prompt_generator::generate_analysis_user_prompt(files, &self.repository_config.path);
-        let mut logger = AnimatedLogger::new("Analyzing Repository".to_string());
-        logger.start();
+        let commit = git_helper::head_commit(&self.planner.repository_config.path).ok();
+        let (files, mut report) = self.planner.scan_files().await?;
 
-        let analyze_data = self.adapter.stream_llm_chat(user_prompt, SYSTEM_ANALYSIS_PROMPT.to_string()).await;
-        logger.stop("Analysis complete").await;
-        let mut analysis_parser = AnalysisParser::new(&analyze_data?.content);
-        let analysis = analysis_parser.parse()?;
+        let analysis = if files.iter().all(|file| file.read_only) {
+            report.log(files.len());
+            log::info!("✨ No changed files to analyze in {}", self.planner.repository_config.name);
+            AnalysisResponse {
+                technology_stack: None,
+                analysis_summary: "No changed files to analyze.".to_string(),
+                changes: Vec::new(),
+                thinking: String::new(),
+            }
+        } else {
+            let mut analysis = match self.planner.analysis_config.chunk_strategy {
+                ChunkStrategy::Single => self.analyze_single_prompt(&files, &mut report).await?,
+                strategy => self.analyze_in_batches(&files, strategy, &mut report).await?,
+            };
+            AnalysisParser::retain_allowed(&mut analysis, &self.planner.filter_for(&files));
+            analysis
+        };
 
         Ok(Rc::new(AnalyzeRepositoryResponse {
             repository_analysis: Rc::new(analysis),
-            repository_config: Rc::new((*self.repository_config).clone())
+            repository_config: Rc::new((*self.planner.repository_config).clone()),
+            commit,
         }))
     }
 
+    async fn analyze_single_prompt(&self, files: &[FileInfo], report: &mut ScanReport) -> AicedResult<AnalysisResponse> {
+        let user_prompt = self.planner.prompt_builder.user_prompt(files);
+
+        let budget = self.planner.ai_config.input_token_budget();
+        let prompt_tokens = self.adapter
+            .count_tokens(AiPhase::Analysis, user_prompt.clone(), self.planner.prompt_builder.system_prompt().to_string(), budget)
+            .await;
+        log::info!("🧮 Analysis prompt: {} tokens (budget {})", prompt_tokens, budget);
+
+        if prompt_tokens <= budget {
+            report.log(files.len());
+            return self.analyze_prompt(user_prompt, "Analyzing Repository".to_string()).await;
+        }
+
+        match self.planner.ai_config.oversize_policy {
+            OversizePolicy::Refuse => {
+                Err(AicedError::AnalysisError {
+                    repository: self.planner.repository_config.name.clone(),
+                    stage: "preflight".to_string(),
+                    reason: format!(
+                        "prompt needs {} tokens but the model accepts {}; narrow the repository or set oversize_policy = \"split\"",
+                        prompt_tokens, budget
+                    ),
+                    recoverable: false,
+                })
+            }
+            OversizePolicy::Warn => {
+                log::warn!("⚠️ Prompt ({} tokens) exceeds the input budget ({}), sending it anyway", prompt_tokens, budget);
+                report.log(files.len());
+                self.analyze_prompt(user_prompt, "Analyzing Repository".to_string()).await
+            }
+            OversizePolicy::Split => self.analyze_in_batches(files, ChunkStrategy::Single, report).await,
+        }
+    }
+
+    async fn analyze_prompt(&self, user_prompt: String, message: String) -> AicedResult<AnalysisResponse> {
+        let mut logger = AnimatedLogger::new(message).with_progress(self.adapter.subscribe_progress());
+        logger.start();
+        let analysis = self.request_analysis(user_prompt).await;
+        logger.stop("Analysis complete").await;
+        analysis
+    }
+
+    async fn request_analysis(&self, user_prompt: String) -> AicedResult<AnalysisResponse> {
+        let analyze_data = match self.planner.use_tools {
+            true => self.adapter.stream_llm_chat_with_tools(
+                AiPhase::Analysis,
+                user_prompt,
+                self.planner.prompt_builder.system_prompt().to_string(),
+                analysis_tools::analysis_tools(self.planner.change_filter.categories()),
+                |tool_call| AnalysisParser::validate_tool_call(tool_call, &self.planner.change_filter),
+            ).await,
+            false => self.adapter.stream_llm_chat_with_continuation(
+                AiPhase::Analysis,
+                user_prompt,
+                self.planner.prompt_builder.system_prompt().to_string(),
+                END_CHANGE_MARKER,
+                self.planner.ai_config.max_continuations,
+            ).await,
+        }?;
+        if analyze_data.cancelled {
+            return self.partial_analysis(analyze_data);
+        }
+
+        let mut analysis = match self.planner.use_tools {
+            true => AnalysisParser::from_tool_calls(&analyze_data.tool_calls)?,
+            false => AnalysisParser::new(&analyze_data.content).parse()?,
+        };
+        analysis.attach_thinking(analyze_data.thinking);
+        Ok(analysis)
+    }
+
+    /// Keeps the part of a cancelled response that parses cleanly: complete change blocks
+    /// in the text protocol, validated calls in tool mode.
+    fn partial_analysis(&self, analyze_data: StreamResult) -> AicedResult<AnalysisResponse> {
+        let parsed = if self.planner.use_tools {
+            let mut analysis = AnalysisParser::from_partial_tool_calls(&analyze_data.tool_calls);
+            if analysis.analysis_summary.is_empty() {
+                analysis.analysis_summary = "Analysis was cancelled before the summary was reported.".to_string();
+            }
+            Ok(analysis)
+        } else {
+            AnalysisParser::new(AicedAdapter::trim_to_block_end(&analyze_data.content, END_CHANGE_MARKER)).parse()
+        };
+
+        match parsed {
+            Ok(mut analysis) => {
+                log::warn!("🛑 Analysis cancelled, keeping {} complete changes", analysis.changes.len());
+                analysis.attach_thinking(analyze_data.thinking);
+                Ok(analysis)
+            }
+            Err(e) => {
+                log::warn!("🛑 Analysis cancelled before any complete result ({})", e);
+                Err(AicedError::cancelled("analysis"))
+            }
+        }
+    }
+
+    async fn analyze_in_batches(&self, files: &[FileInfo], strategy: ChunkStrategy, report: &mut ScanReport) -> AicedResult<AnalysisResponse> {
+        let batches = self.planner.plan_batches(files, strategy, report)?;
+
+        let total = batches.len();
+        if total == 1 {
+            let user_prompt = self.planner.prompt_builder.user_prompt(&batches[0]);
+            return self.analyze_prompt(user_prompt, "Analyzing Repository".to_string()).await;
+        }
+
+        // Concurrent batches share one adapter, so a single logger without live progress
+        // stands in for the per-request status line.
+        let concurrency = self.planner.analysis_config.max_concurrent_batches.clamp(1, total);
+        log::info!("✂️ Splitting analysis into {} batches ({} at a time)", total, concurrency);
+        let mut logger = (concurrency > 1).then(|| AnimatedLogger::new(format!("Analyzing Repository ({} batches)", total)));
+        if let Some(logger) = logger.as_mut() {
+            logger.start();
+        }
+
+        let mut results = futures::stream::iter(batches.into_iter().enumerate())
+            .map(|(index, batch)| self.analyze_batch(index, total, batch, concurrency > 1))
+            .buffered(concurrency);
+        let mut responses = Vec::with_capacity(total);
+        let mut skipped = 0;
+        let mut failure = None;
+
+        while let Some(result) = results.next().await {
+            match result {
+                Ok(response) => responses.push(response),
+                Err(e) if e.is_cancelled() => skipped += 1,
+                Err(e) => {
+                    failure = Some(e);
+                    break;
+                }
+            }
+        }
+        drop(results);
+
+        if let Some(logger) = logger.as_mut() {
+            logger.stop("Analysis complete").await;
+        }
+        if let Some(e) = failure {
+            return Err(e);
+        }
+        if skipped > 0 {
+            // Batches that already finished are still worth keeping.
+            if responses.is_empty() {
+                return Err(AicedError::cancelled("analysis"));
+            }
+            log::warn!("🛑 Skipped {} of {} batches", skipped, total);
+        }
+
+        let summaries: Vec<String> = responses
+            .iter()
+            .map(|r| r.analysis_summary.trim().to_string())
+            .filter(|summary| !summary.is_empty())
+            .collect();
+        let mut merged = AnalysisResponse::merge(responses);
+        if let Some(summary) = self.combine_summaries(&summaries).await {
+            merged.analysis_summary = summary;
+        }
+        Ok(merged)
+    }
+
+    async fn analyze_batch(&self, index: usize, total: usize, batch: Vec<FileInfo>, concurrent: bool) -> AicedResult<AnalysisResponse> {
+        if self.cancellation.is_cancelled() {
+            return Err(AicedError::cancelled("analysis batch"));
+        }
+
+        let user_prompt = self.planner.prompt_builder.user_prompt(&batch);
+        if !concurrent {
+            return self.analyze_prompt(user_prompt, format!("Analyzing Repository (batch {}/{})", index + 1, total)).await;
+        }
+
+        let response = self.request_analysis(user_prompt).await;
+        if let Ok(response) = &response {
+            log::info!("✅ Batch {}/{} complete: {} changes from {} files", index + 1, total, response.changes.len(), batch.len());
+        }
+        response
+    }
+
+    /// Asks the model to rewrite the per-batch summaries as one; keeps them concatenated
+    /// when that fails.
+    async fn combine_summaries(&self, summaries: &[String]) -> Option<String> {
+        if summaries.len() < 2 || self.cancellation.is_cancelled() {
+            return None;
+        }
+
+        let user_prompt = summaries
+            .iter()
+            .enumerate()
+            .map(|(index, summary)| format!("Part {}:\n{}", index + 1, summary))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        match self.adapter.stream_llm_chat(AiPhase::Analysis, user_prompt, MERGE_SUMMARY_PROMPT.to_string()).await {
+            Ok(result) if !result.content.trim().is_empty() => Some(result.content.trim().to_string()),
+            Ok(_) => None,
+            Err(e) => {
+                if !e.is_cancelled() {
+                    log::warn!("⚠️ Could not combine the batch summaries ({}), keeping them as they are", e);
+                }
+                None
+            }
+        }
+    }
+
 }
\ No newline at end of file
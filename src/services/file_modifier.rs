@@ -81,15 +81,15 @@ impl FileModifier {
 
     pub fn apply_change_with_logging(repository_config: Arc<RepositoryConfig>, file_change: &FileChange) -> AicedResult<()> {
         match file_change {
-            FileChange::ModifyFile { file_path, reason: _reason, severity: _severity, category: _category, line_changes } => {
+            FileChange::ModifyFile { file_path, reason: _reason, severity: _severity, category: _category, line_changes, .. } => {
                 let references: Rc<Vec<&LineChange>> = Rc::new(line_changes.iter().collect());
                 FileModifier::validate_file_modifications(&repository_config.path, file_path, Rc::clone(&references))?;
                 FileModifier::apply_file_modifications(&repository_config.path, file_path, Rc::clone(&references))?;
             }
-            FileChange::CreateFile { file_path, reason: _reason, severity: _severity, category: _category, content } => {
+            FileChange::CreateFile { file_path, reason: _reason, severity: _severity, category: _category, content, .. } => {
                 FileModifier::create_file(&repository_config.path, file_path, content)?;
             }
-            FileChange::DeleteFile { file_path, reason: _reason, severity: _severity, category: _category } => {
+            FileChange::DeleteFile { file_path, reason: _reason, severity: _severity, category: _category, .. } => {
                 FileModifier::delete_file(&repository_config.path, file_path)?;
             }
         }
@@ -0,0 +1,57 @@
+use std::time::Duration;
+use reqwest::{Certificate, ClientBuilder, NoProxy, Proxy};
+use crate::errors::{AicedError, AicedResult};
+use crate::structs::config::http_config::HttpConfig;
+
+pub struct HttpClientFactory;
+
+impl HttpClientFactory {
+
+    /// A client builder with the `[http]` proxy, CA bundle and timeouts applied. Callers add
+    /// their own overall request timeout.
+    pub fn builder(http_config: &HttpConfig) -> AicedResult<ClientBuilder> {
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(http_config.connect_timeout_secs))
+            .read_timeout(Duration::from_secs(http_config.read_timeout_secs));
+
+        if let Some(proxy_url) = &http_config.proxy {
+            let proxy = Proxy::all(proxy_url)
+                .map_err(|e| AicedError::configuration_error(
+                    &format!("Invalid proxy URL '{}': {}", proxy_url, e),
+                    Some("http.proxy"),
+                    Some("Use a URL such as http://proxy.example.com:3128")
+                ))?
+                .no_proxy(http_config.no_proxy.as_deref().and_then(NoProxy::from_string));
+            log::debug!("🌐 Using HTTP proxy {}", proxy_url);
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(ca_bundle) = http_config.resolved_ca_bundle() {
+            let pem = std::fs::read(&ca_bundle)
+                .map_err(|e| AicedError::file_error(&ca_bundle.display().to_string(), "read CA bundle", &e.to_string()))?;
+            let certificates = Certificate::from_pem_bundle(&pem)
+                .map_err(|e| AicedError::configuration_error(
+                    &format!("Invalid CA bundle {}: {}", ca_bundle.display(), e),
+                    Some("http.ca_bundle"),
+                    Some("Provide a PEM file containing one or more certificates")
+                ))?;
+            if certificates.is_empty() {
+                return Err(AicedError::configuration_error(
+                    &format!("CA bundle {} contains no certificates", ca_bundle.display()),
+                    Some("http.ca_bundle"),
+                    Some("Provide a PEM file containing one or more certificates")
+                ));
+            }
+
+            log::debug!("🔐 Loaded {} certificate(s) from {}", certificates.len(), ca_bundle.display());
+            for certificate in certificates {
+                builder = builder.add_root_certificate(certificate);
+            }
+            if http_config.ca_bundle_only {
+                builder = builder.tls_built_in_root_certs(false);
+            }
+        }
+
+        Ok(builder)
+    }
+}
@@ -1,6 +1,11 @@
 pub mod code_analyzer;
+pub mod analysis_planner;
 pub mod repo_scanner;
 pub mod file_modifier;
 pub mod analysis_parser;
 pub mod repository_manager;
 pub mod ai;
+pub mod http_client_factory;
+
+pub mod usage_tracker;
+pub mod prompt_builder;
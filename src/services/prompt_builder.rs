@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+use std::fs;
+use crate::errors::{AicedError, AicedResult};
+use crate::helpers::{prompt_generator, prompt_template};
+use crate::helpers::config_helper::ConfigHelper;
+use crate::prompts::category_guides::CATEGORY_GUIDES;
+use crate::prompts::system_analysis_prompt::SYSTEM_ANALYSIS_PROMPT;
+use crate::prompts::system_analysis_tools_prompt::SYSTEM_ANALYSIS_TOOLS_PROMPT;
+use crate::prompts::user_analysis_prompt::USER_ANALYSIS_PROMPT;
+use crate::structs::config::analysis_config::AnalysisConfig;
+use crate::structs::config::prompt_config::PromptConfig;
+use crate::structs::config::repository_config::RepositoryConfig;
+use crate::structs::context_file::ContextFile;
+use crate::structs::file_info::FileInfo;
+
+const SYSTEM_VARIABLES: &[&str] = &["repository_name", "language", "focus_areas", "categories", "category_guide", "guidelines"];
+const USER_VARIABLES: &[&str] = &["repository_name", "language", "focus_areas", "categories", "category_guide", "guidelines", "files"];
+
+/// Renders the analysis prompts from the configured templates, or the built-in ones.
+pub struct PromptBuilder {
+    system_prompt: String,
+    user_template: String,
+    variables: HashMap<&'static str, String>,
+    context: Vec<ContextFile>,
+    repo_path: String,
+}
+
+impl PromptBuilder {
+    pub fn new(prompt_config: &PromptConfig, analysis_config: &AnalysisConfig, repository_config: &RepositoryConfig, use_tools: bool) -> AicedResult<Self> {
+        let system_template = match use_tools {
+            true => Self::load_template(prompt_config.tools_template.as_deref(), SYSTEM_ANALYSIS_TOOLS_PROMPT, "prompts.tools_template", SYSTEM_VARIABLES)?,
+            false => Self::load_template(prompt_config.analysis_template.as_deref(), SYSTEM_ANALYSIS_PROMPT, "prompts.analysis_template", SYSTEM_VARIABLES)?,
+        };
+        let user_template = Self::load_template(prompt_config.user_template.as_deref(), USER_ANALYSIS_PROMPT, "prompts.user_template", USER_VARIABLES)?;
+
+        let guidelines = match &prompt_config.guidelines_file {
+            Some(path) => Self::read_file(path, "prompts.guidelines_file")?,
+            None => prompt_config.guidelines.clone().unwrap_or_default(),
+        };
+        let focus_areas = match analysis_config.focus_areas.is_empty() {
+            true => "All categories".to_string(),
+            false => analysis_config.focus_areas.join(", "),
+        };
+
+        let categories = analysis_config.selected_categories();
+        let variables = HashMap::from([
+            ("repository_name", repository_config.name.clone()),
+            ("language", prompt_config.language.clone()),
+            ("focus_areas", focus_areas),
+            ("categories", categories.join("|")),
+            ("category_guide", Self::category_guide(&categories)),
+            ("guidelines", if guidelines.trim().is_empty() { "None".to_string() } else { guidelines.trim().to_string() }),
+        ]);
+
+        Ok(Self {
+            system_prompt: prompt_template::render(&system_template, &variables),
+            user_template,
+            variables,
+            context: Vec::new(),
+            repo_path: repository_config.path.clone(),
+        })
+    }
+
+    /// Project files sent as read-only context ahead of the analyzed files in every user prompt.
+    pub fn with_context(mut self, context: Vec<ContextFile>) -> Self {
+        self.context = context;
+        self
+    }
+
+    pub fn system_prompt(&self) -> &str {
+        &self.system_prompt
+    }
+
+    pub fn user_prompt(&self, files: &[FileInfo]) -> String {
+        let mut variables = self.variables.clone();
+        variables.insert("files", prompt_generator::generate_files_block(files, &self.context, &self.repo_path));
+        prompt_template::render(&self.user_template, &variables)
+    }
+
+    fn category_guide(categories: &[&str]) -> String {
+        CATEGORY_GUIDES
+            .iter()
+            .filter(|(category, _)| categories.contains(category))
+            .enumerate()
+            .map(|(index, (_, guide))| format!("{}. {}", index + 1, guide))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn load_template(path: Option<&str>, default: &str, field: &str, allowed: &[&str]) -> AicedResult<String> {
+        let Some(path) = path else {
+            return Ok(default.to_string());
+        };
+
+        let template = Self::read_file(path, field)?;
+        prompt_template::validate(&template, allowed).map_err(|e| AicedError::config_error(
+            &format!("Invalid template {}: {}", path, e),
+            Some(field),
+            Some("Use only the documented {{placeholders}}"),
+        ))?;
+        Ok(template)
+    }
+
+    fn read_file(path: &str, field: &str) -> AicedResult<String> {
+        let resolved = ConfigHelper::expand_home(path);
+        fs::read_to_string(&resolved).map_err(|e| AicedError::config_error(
+            &format!("Cannot read {}: {}", resolved.display(), e),
+            Some(field),
+            None,
+        ))
+    }
+}
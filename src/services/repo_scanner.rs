@@ -4,49 +4,156 @@ use std::sync::Arc;
 use tokio::fs;
 use futures::{stream, StreamExt};
 use crate::adapters::aiced_adapter::AicedAdapter;
-use crate::errors::AicedResult;
-use crate::helpers::prompt_generator;
+use crate::enums::ai_phase::AiPhase;
+use crate::enums::file_selection::FileSelection;
+use crate::enums::skip_reason::SkipReason;
+use crate::errors::{AicedError, AicedResult};
+use crate::helpers::{git_helper, prompt_generator};
 use crate::logger::animated_logger::AnimatedLogger;
 use crate::prompts::file_filter_system_prompt::FILE_FILTER_SYSTEM_PROMPT;
 use crate::structs::config::repository_config::RepositoryConfig;
+use crate::structs::config::analysis_config::AnalysisConfig;
 use crate::structs::file_info::FileInfo;
+use crate::structs::file_rules::FileRules;
 use crate::structs::files_cache::FilesCache;
+use crate::structs::ignore_matcher::IgnoreMatcher;
+use crate::structs::scan_report::ScanReport;
 
 pub struct RepoScanner {
     repository_config: Arc<RepositoryConfig>,
     max_concurrent_reads: usize,
-    adapter: Arc<AicedAdapter>
+    adapter: Option<Arc<AicedAdapter>>,
+    since: Option<String>,
+    include_unchanged_neighbours: bool,
+    max_file_size: u64,
+    max_files: usize,
+    file_selection: FileSelection,
+    file_rules: FileRules,
 }
 
 impl RepoScanner {
-    pub fn new(repository_config: Arc<RepositoryConfig>, adapter: Arc<AicedAdapter>) -> Self {
-        Self { repository_config, max_concurrent_reads: 10, adapter  }
+    pub fn new(repository_config: Arc<RepositoryConfig>) -> Self {
+        Self {
+            repository_config,
+            max_concurrent_reads: 10,
+            adapter: None,
+            since: None,
+            include_unchanged_neighbours: false,
+            max_file_size: u64::MAX,
+            max_files: usize::MAX,
+            file_selection: FileSelection::default(),
+            file_rules: FileRules::from_config(&AnalysisConfig::default()),
+        }
+    }
+
+    /// The adapter for the file filter; a scanner without one can only select by rules.
+    pub fn with_adapter(mut self, adapter: Arc<AicedAdapter>) -> Self {
+        self.adapter = Some(adapter);
+        self
+    }
+
+    /// Skips files larger than `max_file_size` bytes and keeps the `max_files` best ranked files.
+    pub fn with_limits(mut self, max_file_size: u64, max_files: usize) -> Self {
+        self.max_file_size = max_file_size;
+        self.max_files = max_files;
+        self
+    }
+
+    /// Picks the files to analyze by `file_rules`, by the model, or by both.
+    pub fn with_file_selection(mut self, file_selection: FileSelection, file_rules: FileRules) -> Self {
+        self.file_selection = file_selection;
+        self.file_rules = file_rules;
+        self
+    }
+
+    /// Limits the scan to files changed since the git ref `since`, optionally with the unchanged
+    /// files of their directories as read-only context.
+    pub fn changed_since(mut self, since: String, include_unchanged_neighbours: bool) -> Self {
+        self.since = Some(since);
+        self.include_unchanged_neighbours = include_unchanged_neighbours;
+        self
     }
 
-    fn get_default_image_patterns(&self) -> HashSet<String> {
-        let image_extensions = vec![
-            "*.jpg", "*.jpeg", "*.png", "*.gif", "*.bmp", "*.tiff", "*.tif",
-            "*.webp", "*.ico", "*.cur",
-            "*.raw", "*.cr2", "*.nef", "*.arw", "*.dng", "*.orf", "*.rw2",
-            "*.svg", "*.eps", "*.ai", "*.pdf",
-            "*.psd", "*.xcf", "*.sketch", "*.fig",
-            "Thumbs.db", ".DS_Store", "*.tmp",
-            "images/", "img/", "assets/images/", "static/images/",
-            "public/images/", "src/assets/", "assets/img/",
-        ];
-
-        image_extensions.into_iter().map(String::from).collect()
+    /// The files to analyze and the report of the files left out, which the caller logs.
+    pub async fn scan_files(&self) -> AicedResult<(Vec<FileInfo>, ScanReport)> {
+        self.scan(true).await
     }
 
-    pub async fn scan_files(&self) -> AicedResult<Vec<FileInfo>> {
-        let patterns = self.load_gitignore(&self.repository_config.path).await?;
-        let repo_files_paths = self.collect_file_paths(Path::new(&self.repository_config.path), &patterns).await?;
+    /// Like `scan_files`, but never calls the file filter: a stale or missing filter cache is
+    /// replaced by the selection rules.
+    pub async fn preview_files(&self) -> AicedResult<(Vec<FileInfo>, ScanReport)> {
+        self.scan(false).await
+    }
+
+    async fn scan(&self, run_filter: bool) -> AicedResult<(Vec<FileInfo>, ScanReport)> {
+        let mut report = ScanReport::default();
+        let paths = self.collect_file_paths(&mut report).await?;
+        let repo_files_paths = self.select_by_rules(paths, &mut report);
+
+        // The changes are split off first so that the file filter only sees the changed
+        // files and their neighbours.
+        let (changed, context) = match &self.since {
+            Some(since) => self.split_by_changes(repo_files_paths.clone(), since)?,
+            None => (repo_files_paths.clone(), Vec::new()),
+        };
+        let candidates: Vec<PathBuf> = changed.iter().chain(&context).cloned().collect();
 
         let cache_path = self.get_cache_file_path();
-        let files_to_analyze = self.get_filtered_files(repo_files_paths, &cache_path).await?;
-        let files = self.process_files(files_to_analyze).await?;
+        // The filter may name files the scan or the rules left out, e.g. ones over the size limit.
+        let selected: HashSet<PathBuf> = self.get_filtered_files(&repo_files_paths, candidates, &cache_path, run_filter, &mut report).await?
+            .into_iter()
+            .collect();
+        let changed: Vec<PathBuf> = changed.into_iter().filter(|path| selected.contains(path)).collect();
+        let context: Vec<PathBuf> = context.into_iter().filter(|path| selected.contains(path)).collect();
 
-        Ok(files)
+        let changed = self.apply_file_limit(changed, self.max_files, &mut report);
+        let context = self.apply_file_limit(context, self.max_files - changed.len(), &mut report);
+
+        let mut files = self.process_files(changed, false, &mut report).await?;
+        files.extend(self.process_files(context, true, &mut report).await?);
+        Ok((files, report))
+    }
+
+    /// Keeps the `limit` best ranked files: source files in a supported language first, then
+    /// files closer to the repository root, then by path. The result is in ranking order.
+    fn apply_file_limit(&self, mut paths: Vec<PathBuf>, limit: usize, report: &mut ScanReport) -> Vec<PathBuf> {
+        paths.sort_by_cached_key(|path| {
+            let supported = FileRules::language_of(path).is_some();
+            (!supported, path.components().count(), path.clone())
+        });
+
+        if paths.len() > limit {
+            for path in paths.split_off(limit) {
+                report.skip(&path, &self.repository_config.path, SkipReason::FileLimit { limit: self.max_files });
+            }
+        }
+        paths
+    }
+
+    fn split_by_changes(&self, files: Vec<PathBuf>, since: &str) -> AicedResult<(Vec<PathBuf>, Vec<PathBuf>)> {
+        let changed_paths: HashSet<PathBuf> = git_helper::changed_files(&self.repository_config.path, since)?
+            .into_iter()
+            .collect();
+        let total = files.len();
+        let (changed, unchanged): (Vec<PathBuf>, Vec<PathBuf>) = files
+            .into_iter()
+            .partition(|path| changed_paths.contains(path));
+        log::info!("🔎 {} of {} files changed since {}", changed.len(), total, since);
+
+        if !self.include_unchanged_neighbours {
+            return Ok((changed, Vec::new()));
+        }
+
+        let changed_dirs: HashSet<&Path> = changed.iter().filter_map(|path| path.parent()).collect();
+        let context: Vec<PathBuf> = unchanged
+            .into_iter()
+            .filter(|path| path.parent().is_some_and(|dir| changed_dirs.contains(dir)))
+            .collect();
+        if !context.is_empty() {
+            log::info!("📎 Adding {} unchanged neighbouring files as read-only context", context.len());
+        }
+
+        Ok((changed, context))
     }
 
     fn get_cache_file_path(&self) -> PathBuf {
@@ -56,14 +163,44 @@ impl RepoScanner {
             .unwrap_or_default()
     }
 
-    async fn get_filtered_files(&self, repo_files_paths: Vec<PathBuf>, cache_path: &Path) -> AicedResult<Vec<PathBuf>> {
+    fn select_by_rules(&self, paths: Vec<PathBuf>, report: &mut ScanReport) -> Vec<PathBuf> {
+        if !self.file_selection.uses_rules() {
+            return paths;
+        }
+
+        let total = paths.len();
+        let selected = self.file_rules.select(paths, &self.repository_config.path, report);
+        log::info!("🧮 Selection rules kept {} of {} files", selected.len(), total);
+        selected
+    }
+
+    /// Runs the file filter on `candidates`, a subset of `all_paths`. The cache holds the
+    /// filter result for `all_paths` and is used whenever it is still valid; a result for a
+    /// smaller set of candidates is not cached. Without `run_filter` a stale cache falls back
+    /// to the selection rules.
+    async fn get_filtered_files(&self, all_paths: &[PathBuf], candidates: Vec<PathBuf>, cache_path: &Path, run_filter: bool, report: &mut ScanReport) -> AicedResult<Vec<PathBuf>> {
+        if !self.file_selection.uses_ai() || candidates.is_empty() {
+            return Ok(candidates);
+        }
+
         if let Some(cache) = FilesCache::load_from_file(cache_path)? {
-            if cache.is_valid_for(&repo_files_paths) {
+            if cache.is_valid_for(all_paths) {
                 return Ok(cache.to_path_bufs());
             }
         }
 
-        self.run_ai_filtering_and_cache(repo_files_paths, cache_path).await
+        if !run_filter {
+            log::warn!("⚠️ The file filter cache is out of date, using the selection rules instead of the model");
+            if self.file_selection.uses_rules() {
+                return Ok(candidates);
+            }
+            return Ok(self.file_rules.select(candidates, &self.repository_config.path, report));
+        }
+
+        if candidates.len() < all_paths.len() {
+            return self.filter_files(candidates).await;
+        }
+        self.run_ai_filtering_and_cache(candidates, cache_path).await
     }
 
     async fn run_ai_filtering_and_cache(&self, repo_files_paths: Vec<PathBuf>, cache_path: &Path) -> AicedResult<Vec<PathBuf>> {
@@ -75,40 +212,48 @@ impl RepoScanner {
         Ok(filtered_paths)
     }
 
-    async fn process_files(&self, file_paths: Vec<PathBuf>) -> AicedResult<Vec<FileInfo>> {
-        let files: Vec<FileInfo> = stream::iter(file_paths)
+    /// Reads the files in the given order. The size is checked again on the content, since
+    /// a file can grow between the scan and the read.
+    async fn process_files(&self, file_paths: Vec<PathBuf>, read_only: bool, report: &mut ScanReport) -> AicedResult<Vec<FileInfo>> {
+        let max_file_size = self.max_file_size;
+        let results: Vec<Result<FileInfo, (PathBuf, SkipReason)>> = stream::iter(file_paths)
             .map(|path| async move {
                 match fs::read_to_string(&path).await {
+                    Ok(content) if content.len() as u64 > max_file_size => {
+                        Err((path, SkipReason::TooLarge { size: content.len() as u64, limit: max_file_size }))
+                    }
                     Ok(content) => Ok(FileInfo {
                         path: path.to_string_lossy().to_string(),
                         content,
+                        read_only,
                     }),
-                    Err(e) => {
-                        log::error!("⚠️ Error reading {}: {}", path.display(), e);
-                        Err(e)
-                    }
-                }
-            })
-            .buffer_unordered(self.max_concurrent_reads)
-            .filter_map(|result| async move {
-                match result {
-                    Ok(file_info) => Some(file_info),
-                    Err(_) => None,
+                    Err(e) => Err((path, SkipReason::Unreadable(e.to_string()))),
                 }
             })
+            .buffered(self.max_concurrent_reads)
             .collect()
             .await;
 
+        let mut files = Vec::with_capacity(results.len());
+        for result in results {
+            match result {
+                Ok(file) => files.push(file),
+                Err((path, reason)) => report.skip(&path, &self.repository_config.path, reason),
+            }
+        }
         Ok(files)
     }
 
     async fn filter_files(&self, repo_files_paths: Vec<PathBuf>) -> AicedResult<Vec<PathBuf>> {
+        let adapter = self.adapter.as_ref().ok_or_else(|| {
+            AicedError::system_error("file filtering", "the scanner has no AI provider; use file_selection = \"rules\"")
+        })?;
         let user_prompt = prompt_generator::generate_file_filter_user_prompt(&repo_files_paths, &self.repository_config.path);
 
-        let mut logger = AnimatedLogger::new("File Filtering".to_string());
+        let mut logger = AnimatedLogger::new("File Filtering".to_string()).with_progress(adapter.subscribe_progress());
         logger.start();
 
-        let filter_data = self.adapter.stream_llm_chat(user_prompt, FILE_FILTER_SYSTEM_PROMPT.to_string()).await;
+        let filter_data = adapter.stream_llm_chat(AiPhase::FileFilter, user_prompt, FILE_FILTER_SYSTEM_PROMPT.to_string()).await;
 
         let content = &filter_data?.content
             .replace("```json", "")
@@ -132,45 +277,40 @@ impl RepoScanner {
         Ok(filtered_files_paths)
     }
 
-    async fn load_gitignore(&self, repo_path: &str) -> AicedResult<HashSet<String>> {
-        let gitignore_path = format!("{}/.gitignore", repo_path);
-        let mut patterns = self.get_default_image_patterns();
-        patterns.insert(String::from(".git/"));
-
-        if let Ok(content) = fs::read_to_string(gitignore_path).await {
-            let gitignore_patterns: HashSet<String> = content
-                .lines()
-                .map(|line| line.trim())
-                .filter(|line| !line.is_empty() && !line.starts_with('#'))
-                .map(|line| line.to_string())
-                .collect();
-
-            patterns.extend(gitignore_patterns);
-        }
-        Ok(patterns)
+    /// The files the scan starts from, sorted: those that pass the ignore rules, the size
+    /// limit and the selection rules, before the file filter and the other limits.
+    pub async fn candidate_files(&self) -> AicedResult<Vec<PathBuf>> {
+        let mut report = ScanReport::default();
+        let paths = self.collect_file_paths(&mut report).await?;
+        let mut paths = self.select_by_rules(paths, &mut report);
+        paths.sort();
+        report.log(paths.len());
+        Ok(paths)
     }
 
-    async fn collect_file_paths(&self, dir: &Path, patterns: &HashSet<String>) -> AicedResult<Vec<PathBuf>> {
+    /// Files that pass the ignore rules, found by walking the repository. Ignored directories
+    /// are not entered, so nothing below them can be re-included.
+    async fn collect_file_paths(&self, report: &mut ScanReport) -> AicedResult<Vec<PathBuf>> {
+        let repo_path = Path::new(&self.repository_config.path);
+        let mut matcher = IgnoreMatcher::for_repo(&self.repository_config.path);
         let mut paths = Vec::new();
-        let mut dirs_to_process = vec![dir.to_path_buf()];
+        let mut dirs_to_process = vec![repo_path.to_path_buf()];
 
         while let Some(current_dir) = dirs_to_process.pop() {
+            matcher.load_dir(&Self::relative_path(repo_path, &current_dir));
             let mut entries = fs::read_dir(&current_dir).await?;
 
             while let Some(entry) = entries.next_entry().await? {
                 let path = entry.path();
                 let metadata = entry.metadata().await?;
 
-                let relative_path = path.strip_prefix(&self.repository_config.path)
-                    .unwrap_or(&path)
-                    .to_string_lossy()
-                    .to_string();
-
-                if self.should_ignore_path(&relative_path, &path, patterns) {
+                if matcher.is_ignored(&Self::relative_path(repo_path, &path), metadata.is_dir()) {
                     continue;
                 }
 
-                if metadata.is_file() {
+                if metadata.is_file() && metadata.len() > self.max_file_size {
+                    report.skip(&path, &self.repository_config.path, SkipReason::TooLarge { size: metadata.len(), limit: self.max_file_size });
+                } else if metadata.is_file() {
                     paths.push(path);
                 } else if metadata.is_dir() {
                     dirs_to_process.push(path);
@@ -180,113 +320,11 @@ impl RepoScanner {
 
         Ok(paths)
     }
-    
-    fn should_ignore_path(&self, relative_path: &str, full_path: &Path, patterns: &HashSet<String>) -> bool {
-        let file_name = full_path.file_name().unwrap_or_default().to_string_lossy();
-
-        if patterns.contains(relative_path) || patterns.contains(&*file_name) {
-            return true;
-        }
-
-        for pattern in patterns {
-            if self.matches_gitignore_pattern(&relative_path, &file_name, full_path, pattern) {
-                return true;
-            }
-        }
-
-        false
-    }
-
-    fn matches_gitignore_pattern(&self, relative_path: &str, file_name: &str, full_path: &Path, pattern: &str) -> bool {
-        // Handle root-relative patterns starting with /
-        if pattern.starts_with('/') {
-            let root_pattern = &pattern[1..]; // Remove leading /
-
-            // For root-relative patterns, only match at the root level
-            if root_pattern.ends_with('/') {
-                // Directory pattern like "/target/"
-                let dir_pattern = &root_pattern[..root_pattern.len()-1];
-                if full_path.is_dir() {
-                    // Check if this is a top-level directory
-                    let path_components: Vec<&str> = relative_path.split('/').collect();
-                    return path_components.len() == 1 && self.matches_glob(&path_components[0], dir_pattern);
-                }
-                return false;
-            } else {
-                // File or directory pattern like "/target" or "/Cargo.lock"
-                let path_components: Vec<&str> = relative_path.split('/').collect();
-                return path_components.len() == 1 && self.matches_glob(&path_components[0], root_pattern);
-            }
-        }
-
-        // Directory patterns ending with /
-        if pattern.ends_with('/') {
-            let dir_pattern = &pattern[..pattern.len()-1];
-            if full_path.is_dir() {
-                return self.matches_glob(relative_path, dir_pattern) || self.matches_glob(file_name, dir_pattern);
-            }
-            return false;
-        }
-
-        // Extension patterns like "*.rs"
-        if pattern.starts_with("*.") {
-            let ext = &pattern[2..];
-            if let Some(file_ext) = full_path.extension() {
-                return file_ext == ext;
-            }
-            return false;
-        }
-
-        // Hidden files/directories starting with .
-        if pattern.starts_with('.') && !pattern.contains('*') {
-            return file_name.starts_with('.') && file_name == pattern;
-        }
-
-        // Glob patterns with wildcards
-        if pattern.contains('*') {
-            return self.matches_glob(relative_path, pattern) || self.matches_glob(file_name, pattern);
-        }
-
-        // Exact match
-        relative_path == pattern || file_name == pattern
-    }
-
-    fn matches_glob(&self, text: &str, pattern: &str) -> bool {
-        // Handle simple cases
-        if pattern == "*" {
-            return true;
-        }
 
-        if pattern == text {
-            return true;
-        }
-
-        // Handle patterns like "*.ext"
-        if pattern.starts_with("*.") {
-            let ext = &pattern[2..];
-            return text.ends_with(&format!(".{}", ext));
-        }
-
-        // Handle patterns like "prefix*"
-        if pattern.ends_with('*') {
-            let prefix = &pattern[..pattern.len()-1];
-            return text.starts_with(prefix);
-        }
-
-        // Handle patterns like "*suffix"
-        if pattern.starts_with('*') {
-            let suffix = &pattern[1..];
-            return text.ends_with(suffix);
-        }
-
-        // Handle patterns like "prefix*suffix"
-        if let Some(star_pos) = pattern.find('*') {
-            let (prefix, suffix_with_star) = pattern.split_at(star_pos);
-            let suffix = &suffix_with_star[1..]; // Remove the '*'
-            return text.starts_with(prefix) && text.ends_with(suffix) && text.len() >= prefix.len() + suffix.len();
-        }
-
-        // No wildcard, check for substring match (common in gitignore)
-        text.contains(pattern)
+    fn relative_path(repo_path: &Path, path: &Path) -> String {
+        path.strip_prefix(repo_path)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .to_string()
     }
-}
\ No newline at end of file
+}
@@ -1,21 +1,28 @@
 use std::rc::Rc;
 use std::sync::Arc;
 use tokio::time::sleep;
-use crate::config::constants::{DEFAULT_SLEEP_BETWEEN_REPOS_SECS, sleep_duration_secs};
+use tokio_util::sync::CancellationToken;
+use crate::config::constants::{DEFAULT_SLEEP_BETWEEN_REPOS_SECS, SINCE_LAST_RUN, sleep_duration_secs};
 use crate::errors::{AicedError, AicedResult};
 use crate::logger::animated_logger::AnimatedLogger;
+use crate::services::analysis_planner::AnalysisPlanner;
 use crate::services::code_analyzer::CodeAnalyzer;
+use crate::services::usage_tracker::UsageTracker;
 use crate::structs::analyze_repository_response::AnalyzeRepositoryResponse;
 use crate::structs::config::config::Config;
 use crate::structs::config::repository_config::RepositoryConfig;
 
 pub struct RepositoryManager {
-    pub config: Rc<Config>
+    pub config: Rc<Config>,
+    usage_tracker: Arc<UsageTracker>,
+    cancellation: CancellationToken,
+    since: Option<String>,
 }
 
 impl RepositoryManager {
-    pub fn new(config: Rc<Config>) -> Self {
-        Self { config }
+    /// `since` comes from the command line and takes precedence over `analysis.since`.
+    pub fn new(config: Rc<Config>, usage_tracker: Arc<UsageTracker>, cancellation: CancellationToken, since: Option<String>) -> Self {
+        Self { config, usage_tracker, cancellation, since }
     }
 
     pub async fn analyze_all_repositories(&mut self, results: &mut Vec<Rc<AnalyzeRepositoryResponse>>) -> AicedResult<()> {
@@ -34,8 +41,14 @@ impl RepositoryManager {
                     "Sleeping for {} seconds", DEFAULT_SLEEP_BETWEEN_REPOS_SECS
                 ));
                 logger.start();
-                sleep(sleep_duration_secs(DEFAULT_SLEEP_BETWEEN_REPOS_SECS)).await;
+                tokio::select! {
+                    _ = sleep(sleep_duration_secs(DEFAULT_SLEEP_BETWEEN_REPOS_SECS)) => {}
+                    _ = self.cancellation.cancelled() => {}
+                }
                 logger.stop("Resume To Next Repository").await;
+                if self.cancellation.is_cancelled() {
+                    return Err(AicedError::cancelled("repository analysis"));
+                }
             }
         }
 
@@ -48,13 +61,48 @@ impl RepositoryManager {
             self.pull_repository(Arc::clone(&repository_config)).await?;
         }
 
-        let analyzer = CodeAnalyzer::new(Arc::clone(&repository_config))?;
+        let analyzer = self.create_analyzer(Arc::clone(&repository_config))?;
         let analyze_repository_response = analyzer.analyze_repository().await?;
         results.push(Rc::clone(&analyze_repository_response));
 
+        // A cancelled analysis still returns what parsed cleanly; it is kept, but the run stops here.
+        if self.cancellation.is_cancelled() {
+            return Err(AicedError::cancelled(&format!("analysis of {}", repository_config.name)));
+        }
+
         Ok(())
     }
 
+    fn create_analyzer(&self, repository_config: Arc<RepositoryConfig>) -> AicedResult<CodeAnalyzer> {
+        let since = self.resolve_since(&repository_config);
+        CodeAnalyzer::new(repository_config, &self.config, Arc::clone(&self.usage_tracker), self.cancellation.clone(), since)
+    }
+
+    /// The prompts and batches of an analysis, without any AI provider.
+    pub fn create_planner(&self, repository_config: Arc<RepositoryConfig>) -> AicedResult<AnalysisPlanner> {
+        let since = self.resolve_since(&repository_config);
+        AnalysisPlanner::new(repository_config, &self.config, since)
+    }
+
+    fn resolve_since(&self, repository_config: &RepositoryConfig) -> Option<String> {
+        let since = self.since.clone().or_else(|| self.config.analysis_config_for(repository_config).since)?;
+        if since != SINCE_LAST_RUN {
+            log::info!("🔎 Analyzing files changed since {}", since);
+            return Some(since);
+        }
+
+        match self.usage_tracker.last_analyzed_commit(&repository_config.name) {
+            Some(commit) => {
+                log::info!("🔎 Analyzing files changed since the last run ({})", &commit[..commit.len().min(12)]);
+                Some(commit)
+            }
+            None => {
+                log::info!("🔎 No previous run of {} recorded, analyzing all files", repository_config.name);
+                None
+            }
+        }
+    }
+
     async fn pull_repository(&self, repo: Arc<RepositoryConfig>) -> AicedResult<()> {
         use std::process::Command;
 
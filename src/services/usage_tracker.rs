@@ -0,0 +1,187 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::enums::ai_phase::AiPhase;
+use crate::enums::ai_provider_kind::AiProviderKind;
+use crate::errors::{AicedError, AicedResult};
+use crate::helpers::config_helper::ConfigHelper;
+use crate::structs::config::budget_config::BudgetConfig;
+use crate::structs::analysis_response::AnalysisResponse;
+use crate::structs::config::config::Config;
+use crate::structs::config::model_pricing::ModelPricing;
+use crate::structs::usage::daily_spend::DailySpend;
+use crate::structs::usage::run_record::RunRecord;
+use crate::structs::usage::token_usage::TokenUsage;
+use crate::structs::usage::usage_entry::UsageEntry;
+
+/// Collects token usage of every provider call in a run, prices it and enforces the
+/// `[budget]` caps. Shared by all adapters of a run.
+pub struct UsageTracker {
+    run_id: String,
+    started_at: u64,
+    pricing: HashMap<String, ModelPricing>,
+    budget: BudgetConfig,
+    ledger_path: PathBuf,
+    entries: Mutex<Vec<UsageEntry>>,
+    unpriced_models: Mutex<HashSet<String>>,
+}
+
+impl UsageTracker {
+    pub fn new(config: &Config) -> Self {
+        let mut pricing = ConfigHelper::default_model_pricing();
+        pricing.extend(config.pricing.clone());
+
+        let ledger_path = Self::aiced_dir().join("spend.toml");
+
+        Self {
+            run_id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            started_at: Self::current_timestamp(),
+            pricing,
+            budget: config.budget.clone(),
+            ledger_path,
+            entries: Mutex::new(Vec::new()),
+            unpriced_models: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Fails once either cap has been reached, so no further provider calls are made. The daily
+    /// spend includes runs that are going on at the same time.
+    pub fn check_budget(&self) -> AicedResult<()> {
+        if let Some(limit) = self.budget.max_cost_per_run {
+            let spent = self.total_cost();
+            if spent >= limit {
+                return Err(AicedError::BudgetExceeded { scope: "per-run".to_string(), limit, spent });
+            }
+        }
+
+        if let Some(limit) = self.budget.max_cost_per_day {
+            let spent = self.spent_today();
+            if spent >= limit {
+                return Err(AicedError::BudgetExceeded { scope: "daily".to_string(), limit, spent });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prices and stores the usage of one provider call; `usage.cost` is filled in here.
+    /// Entries are kept per provider and model, so they also show which fallback served a phase.
+    pub fn record(&self, repository: &str, phase: AiPhase, provider: AiProviderKind, model: &str, mut usage: TokenUsage) {
+        usage.cost = self.price(model, &usage);
+        let cost = usage.cost;
+
+        {
+            let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+            match entries.iter_mut().find(|e| e.repository == repository && e.phase == phase && e.provider == Some(provider) && e.model == model) {
+                Some(entry) => entry.usage.add(&usage),
+                None => entries.push(UsageEntry {
+                    repository: repository.to_string(),
+                    phase,
+                    provider: Some(provider),
+                    model: model.to_string(),
+                    usage,
+                }),
+            }
+        }
+
+        if cost > 0.0 {
+            if let Err(e) = DailySpend::add(&self.ledger_path, Self::current_day(), cost) {
+                log::warn!("⚠️ Failed to update daily spend ledger: {}", e);
+            }
+        }
+    }
+
+    pub fn entries(&self) -> Vec<UsageEntry> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn total_cost(&self) -> f64 {
+        self.entries().iter().map(|e| e.usage.cost).sum()
+    }
+
+    pub fn log_summary(&self) {
+        let entries = self.entries();
+        if entries.is_empty() {
+            return;
+        }
+
+        log::info!("💰 Usage summary:");
+        let mut total = TokenUsage::default();
+        for entry in &entries {
+            log::info!(
+                "   {} / {} ({}{}): {} requests, {} input + {} output tokens, cache {} written / {} read, ${:.4}",
+                entry.repository, entry.phase,
+                entry.provider.map(|p| format!("{}/", p)).unwrap_or_default(), entry.model,
+                entry.usage.requests, entry.usage.input_tokens, entry.usage.output_tokens,
+                entry.usage.cache_creation_tokens, entry.usage.cache_read_tokens, entry.usage.cost
+            );
+            total.add(&entry.usage);
+        }
+        log::info!(
+            "   Total: {} requests, {} input + {} output tokens, cache {} written / {} read, ${:.4} (today ${:.4})",
+            total.requests, total.input_tokens, total.output_tokens,
+            total.cache_creation_tokens, total.cache_read_tokens, total.cost, self.spent_today()
+        );
+    }
+
+    pub fn save_run(&self, repositories: Vec<String>, thinking: HashMap<String, String>, commits: HashMap<String, String>, cancelled_analyses: Option<HashMap<String, AnalysisResponse>>) -> AicedResult<()> {
+        let record = RunRecord {
+            id: self.run_id.clone(),
+            started_at: self.started_at,
+            finished_at: Self::current_timestamp(),
+            repositories,
+            usage: self.entries(),
+            total_cost: self.total_cost(),
+            thinking,
+            commits,
+            cancelled: cancelled_analyses.is_some(),
+            cancelled_analyses: cancelled_analyses.unwrap_or_default(),
+        };
+        record.save_to_dir(&Self::runs_dir())
+    }
+
+    pub fn last_analyzed_commit(&self, repository: &str) -> Option<String> {
+        RunRecord::last_commit_for(&Self::runs_dir(), repository)
+    }
+
+    /// Cost of usage that has not been recorded yet, such as a response still streaming.
+    pub fn estimate_cost(&self, model: &str, usage: &TokenUsage) -> f64 {
+        self.price(model, usage)
+    }
+
+    fn price(&self, model: &str, usage: &TokenUsage) -> f64 {
+        match self.pricing.get(model) {
+            Some(pricing) => pricing.cost(usage),
+            None => {
+                if self.unpriced_models.lock().unwrap_or_else(|e| e.into_inner()).insert(model.to_string()) {
+                    log::warn!("⚠️ No pricing configured for model '{}', counting its usage as free", model);
+                }
+                0.0
+            }
+        }
+    }
+
+    fn spent_today(&self) -> f64 {
+        DailySpend::load_for_day(&self.ledger_path, Self::current_day()).cost
+    }
+
+    fn aiced_dir() -> PathBuf {
+        dirs::home_dir().map(|d| d.join("aiced")).unwrap_or_default()
+    }
+
+    fn runs_dir() -> PathBuf {
+        Self::aiced_dir().join("runs")
+    }
+
+    fn current_timestamp() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    }
+
+    fn current_day() -> u64 {
+        Self::current_timestamp() / 86_400
+    }
+}
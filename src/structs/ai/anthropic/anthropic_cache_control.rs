@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AnthropicCacheControl {
+    pub r#type: String,
+}
+
+impl AnthropicCacheControl {
+    pub fn ephemeral() -> Self {
+        Self { r#type: "ephemeral".to_string() }
+    }
+}
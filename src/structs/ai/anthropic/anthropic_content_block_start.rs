@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+/// Opening of a response content block; `id` and `name` are only set for `tool_use` blocks.
+#[derive(Deserialize, Debug, Clone)]
+pub struct AnthropicContentBlockStart {
+    #[serde(rename = "type")]
+    pub block_type: String,
+    pub id: Option<String>,
+    pub name: Option<String>,
+}
@@ -5,4 +5,6 @@ pub struct AnthropicContentDelta {
     #[serde(rename = "type")]
     pub delta_type: String,
     pub text: Option<String>,
+    pub thinking: Option<String>,
+    pub partial_json: Option<String>,
 }
\ No newline at end of file
@@ -1,7 +1,8 @@
 use serde::Serialize;
+use crate::enums::anthropic_content_block::AnthropicContentBlock;
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Debug, Clone)]
 pub struct AnthropicMessage {
     pub role: String,
-    pub content: String,
+    pub content: Vec<AnthropicContentBlock>,
 }
@@ -1,14 +1,20 @@
 use serde::Serialize;
+use crate::enums::anthropic_content_block::AnthropicContentBlock;
 use crate::structs::ai::anthropic::anthropic_message::AnthropicMessage;
 use crate::structs::ai::anthropic::anthropic_thinking::AnthropicThinking;
+use crate::structs::ai::anthropic::anthropic_tool::AnthropicTool;
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct AnthropicMessageRequest {
     pub model: String,
-    pub system: String,
+    pub system: Vec<AnthropicContentBlock>,
     pub max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f32>,
     pub messages: Vec<AnthropicMessage>,
     pub stream: bool,
-    pub thinking: AnthropicThinking,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<AnthropicThinking>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<AnthropicTool>>,
 }
\ No newline at end of file
@@ -4,4 +4,8 @@ use serde::Deserialize;
 pub struct AnthropicStartUsageInfo {
     pub input_tokens: u32,
     pub output_tokens: u32,
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<u32>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<u32>,
 }
\ No newline at end of file
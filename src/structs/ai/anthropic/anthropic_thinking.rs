@@ -1,6 +1,6 @@
 use serde::Serialize;
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct AnthropicThinking {
     pub r#type: String,
     pub budget_tokens: u32,
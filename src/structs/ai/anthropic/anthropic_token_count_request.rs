@@ -1,10 +1,11 @@
 use serde::Serialize;
+use crate::enums::anthropic_content_block::AnthropicContentBlock;
 use crate::structs::ai::anthropic::anthropic_message::AnthropicMessage;
 
 #[derive(Serialize)]
 #[derive(Debug)]
 pub struct AnthropicTokenCountRequest {
     pub model: String,
-    pub system: String,
+    pub system: Vec<AnthropicContentBlock>,
     pub messages: Vec<AnthropicMessage>
 }
\ No newline at end of file
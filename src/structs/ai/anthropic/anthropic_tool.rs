@@ -0,0 +1,8 @@
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone)]
+pub struct AnthropicTool {
+    pub name: String,
+    pub description: String,
+    pub input_schema: serde_json::Value,
+}
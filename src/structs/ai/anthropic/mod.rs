@@ -8,3 +8,8 @@ pub mod anthropic_token_count_response;
 pub mod anthropic_message_start_info;
 pub mod anthropic_start_usage_info;
 pub mod anthropic_finish_usage_info;
+
+pub mod anthropic_cache_control;
+
+pub mod anthropic_tool;
+pub mod anthropic_content_block_start;
\ No newline at end of file
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use crate::enums::chat_role::ChatRole;
+use crate::structs::ai::tool_call::ToolCall;
+use crate::structs::ai::tool_result::ToolResult;
+
+/// One turn of a provider-neutral conversation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+    /// Marks the end of a prefix that providers with prompt caching may cache.
+    #[serde(default)]
+    pub cache_breakpoint: bool,
+    /// Tool invocations made by an assistant turn.
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
+    /// Answers to the previous assistant turn's tool invocations.
+    #[serde(default)]
+    pub tool_results: Vec<ToolResult>,
+}
+
+impl ChatMessage {
+    fn new(role: ChatRole, content: String) -> Self {
+        Self { role, content, cache_breakpoint: false, tool_calls: Vec::new(), tool_results: Vec::new() }
+    }
+
+    pub fn system(content: impl Into<String>) -> Self {
+        Self::new(ChatRole::System, content.into())
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self::new(ChatRole::User, content.into())
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self::new(ChatRole::Assistant, content.into())
+    }
+
+    pub fn assistant_tool_calls(content: impl Into<String>, tool_calls: Vec<ToolCall>) -> Self {
+        Self { tool_calls, ..Self::new(ChatRole::Assistant, content.into()) }
+    }
+
+    pub fn tool_results(tool_results: Vec<ToolResult>) -> Self {
+        Self { tool_results, ..Self::new(ChatRole::User, String::new()) }
+    }
+
+    pub fn cached(mut self) -> Self {
+        self.cache_breakpoint = true;
+        self
+    }
+}
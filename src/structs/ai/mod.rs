@@ -1,3 +1,10 @@
 pub mod anthropic;
 pub mod openai;
-pub(crate) mod api_error;
\ No newline at end of file
+pub mod ollama;
+pub mod retry_policy;
+pub(crate) mod api_error;
+pub mod chat_message;
+pub mod tool_definition;
+pub mod tool_call;
+pub mod tool_result;
+pub mod serving_provider;
@@ -0,0 +1,4 @@
+pub mod ollama_message;
+pub mod ollama_options;
+pub mod ollama_chat_request;
+pub mod ollama_chat_chunk;
\ No newline at end of file
@@ -0,0 +1,18 @@
+use serde::Deserialize;
+use crate::structs::ai::ollama::ollama_message::OllamaMessage;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OllamaChatChunk {
+    #[serde(default)]
+    pub message: Option<OllamaMessage>,
+    #[serde(default)]
+    pub done: bool,
+    #[serde(default)]
+    pub done_reason: Option<String>,
+    #[serde(default)]
+    pub prompt_eval_count: Option<u32>,
+    #[serde(default)]
+    pub eval_count: Option<u32>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
@@ -0,0 +1,11 @@
+use serde::Serialize;
+use crate::structs::ai::ollama::ollama_message::OllamaMessage;
+use crate::structs::ai::ollama::ollama_options::OllamaOptions;
+
+#[derive(Serialize, Debug)]
+pub struct OllamaChatRequest {
+    pub model: String,
+    pub messages: Vec<OllamaMessage>,
+    pub stream: bool,
+    pub options: OllamaOptions,
+}
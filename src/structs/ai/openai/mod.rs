@@ -1,2 +1,9 @@
 pub mod openai_request;
-pub mod openai_message;
\ No newline at end of file
+pub mod openai_message;
+pub mod openai_stream_options;
+pub mod openai_stream_chunk;
+pub mod openai_stream_choice;
+pub mod openai_stream_delta;
+pub mod openai_usage;
+pub mod openai_error_response;
+pub mod openai_error_body;
\ No newline at end of file
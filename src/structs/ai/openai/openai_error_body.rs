@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OpenAIErrorBody {
+    pub message: String,
+    #[serde(default, rename = "type")]
+    pub error_type: Option<String>,
+}
@@ -0,0 +1,7 @@
+use serde::Deserialize;
+use crate::structs::ai::openai::openai_error_body::OpenAIErrorBody;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OpenAIErrorResponse {
+    pub error: OpenAIErrorBody,
+}
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use crate::structs::ai::openai::openai_message::OpenAIMessage;
+use crate::structs::ai::openai::openai_stream_options::OpenAIStreamOptions;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct OpenAIRequest {
@@ -11,6 +12,8 @@ pub struct OpenAIRequest {
     pub temperature: Option<f32>,
     pub stream: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_options: Option<OpenAIStreamOptions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub top_p: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub frequency_penalty: Option<f32>,
@@ -0,0 +1,9 @@
+use serde::Deserialize;
+use crate::structs::ai::openai::openai_stream_delta::OpenAIStreamDelta;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OpenAIStreamChoice {
+    pub delta: OpenAIStreamDelta,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
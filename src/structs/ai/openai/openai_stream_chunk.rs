@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use crate::structs::ai::openai::openai_stream_choice::OpenAIStreamChoice;
+use crate::structs::ai::openai::openai_usage::OpenAIUsage;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OpenAIStreamChunk {
+    #[serde(default)]
+    pub choices: Vec<OpenAIStreamChoice>,
+    #[serde(default)]
+    pub usage: Option<OpenAIUsage>,
+}
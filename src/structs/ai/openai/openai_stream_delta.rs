@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct OpenAIStreamDelta {
+    #[serde(default)]
+    pub content: Option<String>,
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+use crate::structs::config::ai_config::AiConfig;
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(ai_config: &AiConfig) -> Self {
+        Self {
+            max_retries: ai_config.max_retries,
+            base_delay: Duration::from_millis(ai_config.retry_base_delay_ms),
+            max_delay: Duration::from_millis(ai_config.retry_max_delay_ms),
+        }
+    }
+
+    /// Exponential backoff with "equal jitter": half of the capped delay is fixed and the
+    /// other half is random, so concurrent runs hitting the same limit spread out.
+    /// A server-provided `retry-after` always wins when it is longer.
+    pub fn delay_for(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        let exponential = self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let half = exponential / 2;
+        let jittered = half + half.mul_f64(rand::random::<f64>());
+
+        match retry_after {
+            Some(retry_after) => retry_after.max(jittered),
+            None => jittered,
+        }
+    }
+}
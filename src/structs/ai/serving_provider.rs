@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use crate::enums::ai_provider_kind::AiProviderKind;
+use crate::traits::ai_provider::AiProvider;
+
+/// The provider and model that produced a stream item. Usage is attributed to it rather
+/// than to whatever a fallback chain serves at the time the usage is recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServingProvider {
+    pub kind: AiProviderKind,
+    pub model: String,
+    pub billable: bool,
+}
+
+impl ServingProvider {
+    pub fn of(provider: &dyn AiProvider) -> Self {
+        Self {
+            kind: provider.kind(),
+            model: provider.model().to_string(),
+            billable: provider.is_billable(),
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// A complete tool invocation, with its streamed arguments already parsed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub input: serde_json::Value,
+}
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+/// A tool offered to the model, described by a JSON schema for its input.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolDefinition {
+    pub name: String,
+    pub description: String,
+    pub input_schema: serde_json::Value,
+}
@@ -7,4 +7,73 @@ pub struct AnalysisResponse {
     pub technology_stack: Option<TechnologyStack>,
     pub analysis_summary: String,
     pub changes: Vec<FileChange>,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub thinking: String,
+}
+
+impl AnalysisResponse {
+    /// Combines the results of several batches analysed independently. The summaries are
+    /// only concatenated; `CodeAnalyzer` asks the model to rewrite them as one.
+    pub fn merge(responses: Vec<AnalysisResponse>) -> AnalysisResponse {
+        let mut merged = AnalysisResponse {
+            technology_stack: None,
+            analysis_summary: String::new(),
+            changes: Vec::new(),
+            thinking: String::new(),
+        };
+
+        for response in responses {
+            match (&mut merged.technology_stack, response.technology_stack) {
+                (Some(stack), Some(other)) => stack.merge(other),
+                (stack @ None, other) => *stack = other,
+                (Some(_), None) => {}
+            }
+            if !response.analysis_summary.trim().is_empty() {
+                if !merged.analysis_summary.is_empty() {
+                    merged.analysis_summary.push_str("\n\n");
+                }
+                merged.analysis_summary.push_str(response.analysis_summary.trim());
+            }
+            merged.changes.extend(response.changes);
+            if !response.thinking.is_empty() {
+                if !merged.thinking.is_empty() {
+                    merged.thinking.push_str("\n\n");
+                }
+                merged.thinking.push_str(&response.thinking);
+            }
+        }
+
+        merged
+    }
+
+    /// Keeps the model's thinking and gives every change the paragraphs of it that
+    /// mention the changed file by its path, as a best-effort explanation for reviewers.
+    /// File names alone are not enough: `mod.rs` or `index.ts` would pick up paragraphs
+    /// about every other file of that name.
+    pub fn attach_thinking(&mut self, thinking: String) {
+        let paragraphs: Vec<&str> = thinking
+            .split("\n\n")
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty())
+            .collect();
+
+        for change in &mut self.changes {
+            let file_path = change.get_file_path().trim_start_matches('/').to_string();
+            if file_path.is_empty() {
+                continue;
+            }
+
+            let relevant: Vec<&str> = paragraphs
+                .iter()
+                .filter(|p| p.contains(&file_path))
+                .copied()
+                .collect();
+
+            if !relevant.is_empty() {
+                change.set_rationale(Some(relevant.join("\n\n")));
+            }
+        }
+
+        self.thinking = thinking;
+    }
+}
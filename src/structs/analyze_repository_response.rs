@@ -6,4 +6,6 @@ use crate::structs::config::repository_config::RepositoryConfig;
 pub struct AnalyzeRepositoryResponse {
     pub repository_analysis: Rc<AnalysisResponse>,
     pub repository_config: Rc<RepositoryConfig>,
+    /// `HEAD` of the repository when it was analyzed, if it is a git repository.
+    pub commit: Option<String>,
 } 
\ No newline at end of file
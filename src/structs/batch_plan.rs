@@ -0,0 +1,9 @@
+use crate::structs::file_info::FileInfo;
+
+/// The analysis batches of a scan, and the files that are larger than a whole batch with
+/// their estimated tokens. What happens to those is up to the `oversize_policy`.
+#[derive(Default)]
+pub struct BatchPlan {
+    pub batches: Vec<Vec<FileInfo>>,
+    pub oversized: Vec<(FileInfo, u32)>,
+}
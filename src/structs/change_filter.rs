@@ -0,0 +1,50 @@
+use std::collections::HashSet;
+use crate::enums::file_change::FileChange;
+use crate::helpers::config_helper::ConfigHelper;
+
+/// Which proposed changes a run accepts: only the selected categories, and nothing for
+/// read-only files.
+#[derive(Clone)]
+pub struct ChangeFilter {
+    categories: Vec<&'static str>,
+    protected_files: HashSet<String>,
+}
+
+impl ChangeFilter {
+    pub fn new(categories: Vec<&'static str>) -> Self {
+        Self { categories, protected_files: HashSet::new() }
+    }
+
+    /// Paths relative to the repository root; a leading `/` is ignored.
+    pub fn protect<I, S>(&mut self, paths: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.protected_files.extend(paths.into_iter().map(|path| Self::normalize_path(path.as_ref())));
+    }
+
+    pub fn categories(&self) -> &[&'static str] {
+        &self.categories
+    }
+
+    pub fn is_protected(&self, path: &str) -> bool {
+        self.protected_files.contains(&Self::normalize_path(path))
+    }
+
+    pub fn check(&self, change: &FileChange) -> Result<(), String> {
+        if self.is_protected(change.get_file_path()) {
+            return Err(format!("{} is read-only context and cannot be changed", change.get_file_path()));
+        }
+
+        let category = ConfigHelper::normalize_category(change.get_category());
+        if !self.categories.contains(&category.as_str()) {
+            return Err(format!("category '{}' was not requested; only report {}", change.get_category(), self.categories.join(", ")));
+        }
+        Ok(())
+    }
+
+    fn normalize_path(path: &str) -> String {
+        path.trim().trim_start_matches("./").trim_start_matches('/').to_string()
+    }
+}
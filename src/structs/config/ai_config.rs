@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::enums::ai_phase::AiPhase;
+use crate::enums::ai_provider_kind::AiProviderKind;
+use crate::enums::output_mode::OutputMode;
+use crate::enums::oversize_policy::OversizePolicy;
+use crate::helpers::config_helper::ConfigHelper;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AiConfig {
+    #[serde(default)]
+    pub provider: AiProviderKind,
+
+    #[serde(default)]
+    pub model: Option<String>,
+
+    #[serde(default)]
+    pub base_url: Option<String>,
+
+    #[serde(default)]
+    pub max_tokens: Option<u32>,
+
+    #[serde(default)]
+    pub temperature: Option<f32>,
+
+    #[serde(default)]
+    pub context_window: Option<u32>,
+
+    #[serde(default)]
+    pub oversize_policy: OversizePolicy,
+
+    #[serde(default)]
+    pub output_mode: OutputMode,
+
+    #[serde(default = "ConfigHelper::default_thinking")]
+    pub thinking: bool,
+
+    #[serde(default = "ConfigHelper::default_thinking_budget")]
+    pub thinking_budget: u32,
+
+    #[serde(default = "ConfigHelper::default_prompt_caching")]
+    pub prompt_caching: bool,
+
+    #[serde(default = "ConfigHelper::default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+
+    #[serde(default = "ConfigHelper::default_max_retries")]
+    pub max_retries: u32,
+
+    #[serde(default = "ConfigHelper::default_retry_base_delay_ms")]
+    pub retry_base_delay_ms: u64,
+
+    #[serde(default = "ConfigHelper::default_retry_max_delay_ms")]
+    pub retry_max_delay_ms: u64,
+
+    #[serde(default = "ConfigHelper::default_max_continuations")]
+    pub max_continuations: u32,
+
+    /// Providers to try in order when this one keeps failing with retryable errors.
+    #[serde(default)]
+    pub fallbacks: Vec<AiConfig>,
+
+    #[serde(default)]
+    pub phases: HashMap<AiPhase, AiConfig>,
+}
+
+impl AiConfig {
+    /// The settings for `phase`. Phase tables hold the top-level settings they leave out,
+    /// merged in when the configuration is loaded.
+    pub fn for_phase(&self, phase: AiPhase) -> &AiConfig {
+        self.phases.get(&phase).unwrap_or(self)
+    }
+
+    pub fn resolved_model(&self) -> String {
+        self.model.clone().unwrap_or_else(|| self.provider.default_model().to_string())
+    }
+
+    pub fn resolved_base_url(&self) -> String {
+        self.base_url
+            .clone()
+            .unwrap_or_else(|| self.provider.default_base_url().to_string())
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// Tokens left for the prompt once the response budget is reserved from the context window.
+    pub fn input_token_budget(&self) -> u32 {
+        let context_window = self.context_window.unwrap_or(self.provider.default_context_window());
+        context_window.saturating_sub(self.resolved_max_tokens())
+    }
+
+    pub fn resolved_max_tokens(&self) -> u32 {
+        self.max_tokens.unwrap_or(self.provider.default_max_tokens())
+    }
+
+    pub fn resolved_api_key_env(&self) -> Option<String> {
+        self.api_key_env
+            .clone()
+            .or_else(|| self.provider.default_api_key_env().map(String::from))
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if let Some(temperature) = self.temperature {
+            if !(0.0..=2.0).contains(&temperature) {
+                errors.push(format!("ai.temperature must be between 0.0 and 2.0, got {}", temperature));
+            }
+        }
+
+        if self.input_token_budget() == 0 {
+            errors.push("ai.max_tokens must be lower than ai.context_window".to_string());
+        }
+
+        if self.request_timeout_secs == 0 {
+            errors.push("ai.request_timeout_secs must be greater than 0".to_string());
+        }
+
+        if self.retry_base_delay_ms > self.retry_max_delay_ms {
+            errors.push(format!(
+                "ai.retry_base_delay_ms ({}) must not exceed ai.retry_max_delay_ms ({})",
+                self.retry_base_delay_ms, self.retry_max_delay_ms
+            ));
+        }
+
+        if self.provider == AiProviderKind::Anthropic && self.thinking {
+            let max_tokens = self.resolved_max_tokens();
+            if self.thinking_budget >= max_tokens {
+                errors.push(format!(
+                    "ai.thinking_budget ({}) must be lower than ai.max_tokens ({})",
+                    self.thinking_budget, max_tokens
+                ));
+            }
+            if self.temperature.is_some_and(|t| t != 1.0) {
+                errors.push("ai.temperature must be 1.0 (or unset) when extended thinking is enabled".to_string());
+            }
+        }
+
+        for (index, fallback) in self.fallbacks.iter().enumerate() {
+            if !fallback.fallbacks.is_empty() || !fallback.phases.is_empty() {
+                errors.push(format!("ai.fallbacks[{}] cannot define its own fallbacks or phases", index));
+            }
+            errors.extend(fallback.validate().into_iter().map(|e| format!("ai.fallbacks[{}]: {}", index, e)));
+        }
+
+        for (phase, phase_config) in &self.phases {
+            if !phase_config.phases.is_empty() {
+                errors.push(format!("ai.phases.{} cannot define nested phases", phase));
+            }
+            errors.extend(phase_config.validate().into_iter().map(|e| format!("ai.phases.{}: {}", phase, e)));
+        }
+
+        errors
+    }
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        Self {
+            provider: AiProviderKind::default(),
+            model: None,
+            base_url: None,
+            max_tokens: None,
+            temperature: None,
+            context_window: None,
+            oversize_policy: OversizePolicy::default(),
+            output_mode: OutputMode::default(),
+            thinking: ConfigHelper::default_thinking(),
+            thinking_budget: ConfigHelper::default_thinking_budget(),
+            prompt_caching: ConfigHelper::default_prompt_caching(),
+            request_timeout_secs: ConfigHelper::default_request_timeout_secs(),
+            api_key_env: None,
+            max_retries: ConfigHelper::default_max_retries(),
+            retry_base_delay_ms: ConfigHelper::default_retry_base_delay_ms(),
+            retry_max_delay_ms: ConfigHelper::default_retry_max_delay_ms(),
+            max_continuations: ConfigHelper::default_max_continuations(),
+            fallbacks: Vec::new(),
+            phases: HashMap::new(),
+        }
+    }
+}
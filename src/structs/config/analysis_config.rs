@@ -1,8 +1,15 @@
+use crate::config::constants::{ANALYSIS_CATEGORIES, SUPPORTED_FILE_EXTENSIONS};
+use crate::enums::chunk_strategy::ChunkStrategy;
+use crate::enums::file_selection::FileSelection;
 use crate::helpers::config_helper::ConfigHelper;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct AnalysisConfig {
+    /// How files are picked: by the rules below, by the model, or by both.
+    #[serde(default)]
+    pub file_selection: FileSelection,
+
     #[serde(default = "ConfigHelper::default_exclude_patterns")]
     pub exclude_patterns: Vec<String>,
 
@@ -18,22 +25,43 @@ pub struct AnalysisConfig {
     #[serde(default = "ConfigHelper::default_languages")]
     pub languages: Vec<String>,
 
-    #[serde(default)]
+    #[serde(default = "ConfigHelper::default_skip_tests")]
     pub skip_tests: bool,
 
     #[serde(default)]
     pub focus_areas: Vec<String>,
 
-    #[serde(default = "ConfigHelper::default_chunk_strategy")]
-    pub chunk_strategy: String,
+    /// Categories to analyze, e.g. `["security", "bugs"]`; all of them when empty.
+    #[serde(default)]
+    pub categories: Vec<String>,
+
+    #[serde(default)]
+    pub chunk_strategy: ChunkStrategy,
+
+    /// Upper bound for the prompt of one batch; defaults to the model's input budget.
+    #[serde(default)]
+    pub max_batch_tokens: Option<u32>,
+
+    #[serde(default = "ConfigHelper::default_max_concurrent_batches")]
+    pub max_concurrent_batches: usize,
 
     #[serde(default)]
     pub file_extensions: Vec<String>,
+
+    /// Only analyze files changed since this git ref, or since the last run with "last-run".
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// With `since`, also send unchanged files from the directories of changed files as
+    /// read-only context.
+    #[serde(default)]
+    pub include_unchanged_neighbours: bool,
 }
 
 impl Default for AnalysisConfig {
     fn default() -> Self {
         Self {
+            file_selection: FileSelection::default(),
             exclude_patterns: ConfigHelper::default_exclude_patterns(),
             include_patterns: ConfigHelper::default_include_patterns(),
             max_file_size: ConfigHelper::default_max_file_size(),
@@ -41,8 +69,79 @@ impl Default for AnalysisConfig {
             languages: ConfigHelper::default_languages(),
             skip_tests: ConfigHelper::default_skip_tests(),
             focus_areas: Vec::new(),
-            chunk_strategy: ConfigHelper::default_chunk_strategy(),
+            categories: Vec::new(),
+            chunk_strategy: ChunkStrategy::default(),
+            max_batch_tokens: None,
+            max_concurrent_batches: ConfigHelper::default_max_concurrent_batches(),
             file_extensions: vec![],
+            since: None,
+            include_unchanged_neighbours: false,
+        }
+    }
+}
+
+impl AnalysisConfig {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if self.max_batch_tokens == Some(0) {
+            errors.push("analysis.max_batch_tokens must be greater than 0".to_string());
+        }
+        if self.max_concurrent_batches == 0 {
+            errors.push("analysis.max_concurrent_batches must be at least 1".to_string());
+        }
+
+        if let Err(e) = ConfigHelper::parse_size(&self.max_file_size) {
+            errors.push(format!("analysis.max_file_size: {}", e));
+        }
+        if self.max_files == 0 {
+            errors.push("analysis.max_files must be at least 1".to_string());
+        }
+
+        let unknown_languages: Vec<&str> = self.languages
+            .iter()
+            .filter(|language| !SUPPORTED_FILE_EXTENSIONS.iter().any(|(_, known)| known.eq_ignore_ascii_case(language)))
+            .map(String::as_str)
+            .collect();
+        if !unknown_languages.is_empty() {
+            let mut known: Vec<&str> = SUPPORTED_FILE_EXTENSIONS.iter().map(|(_, language)| *language).collect();
+            known.dedup();
+            errors.push(format!(
+                "analysis.languages has unknown entries: {} (expected: {})",
+                unknown_languages.join(", "),
+                known.join(", ")
+            ));
         }
+
+        let unknown: Vec<&str> = self.categories
+            .iter()
+            .filter(|category| !ANALYSIS_CATEGORIES.contains(&ConfigHelper::normalize_category(category).as_str()))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            errors.push(format!(
+                "analysis.categories has unknown entries: {} (expected: {})",
+                unknown.join(", "),
+                ANALYSIS_CATEGORIES.iter().map(|c| c.to_lowercase()).collect::<Vec<_>>().join(", ")
+            ));
+        }
+
+        errors
+    }
+
+    pub fn max_file_size_bytes(&self) -> u64 {
+        ConfigHelper::parse_size(&self.max_file_size)
+            .or_else(|_| ConfigHelper::parse_size(&ConfigHelper::default_max_file_size()))
+            .unwrap_or(u64::MAX)
+    }
+
+    /// The selected categories in their prompt form and canonical order.
+    pub fn selected_categories(&self) -> Vec<&'static str> {
+        let requested: Vec<String> = self.categories.iter().map(|c| ConfigHelper::normalize_category(c)).collect();
+        ANALYSIS_CATEGORIES
+            .iter()
+            .copied()
+            .filter(|category| requested.is_empty() || requested.iter().any(|r| r == category))
+            .collect()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct BudgetConfig {
+    #[serde(default)]
+    pub max_cost_per_run: Option<f64>,
+
+    #[serde(default)]
+    pub max_cost_per_day: Option<f64>,
+}
+
+impl BudgetConfig {
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        if self.max_cost_per_run.is_some_and(|cap| cap <= 0.0) {
+            errors.push("budget.max_cost_per_run must be greater than 0".to_string());
+        }
+        if self.max_cost_per_day.is_some_and(|cap| cap <= 0.0) {
+            errors.push("budget.max_cost_per_day must be greater than 0".to_string());
+        }
+
+        errors
+    }
+}
@@ -0,0 +1,21 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use crate::enums::cassette_mode::CassetteMode;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct CassetteConfig {
+    #[serde(default)]
+    pub mode: CassetteMode,
+
+    #[serde(default)]
+    pub dir: Option<String>,
+}
+
+impl CassetteConfig {
+    pub fn resolved_dir(&self) -> PathBuf {
+        match &self.dir {
+            Some(dir) => PathBuf::from(dir),
+            None => dirs::home_dir().map(|d| d.join("aiced/cassettes")).unwrap_or_default(),
+        }
+    }
+}
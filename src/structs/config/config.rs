@@ -1,7 +1,20 @@
+use std::collections::HashMap;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use crate::config::config_manager::ConfigManager;
+use crate::errors::{AicedError, AicedResult};
+use crate::helpers::config_helper::ConfigHelper;
+use crate::structs::config::ai_config::AiConfig;
+use crate::structs::config::analysis_config::AnalysisConfig;
+use crate::structs::config::budget_config::BudgetConfig;
+use crate::structs::config::cassette_config::CassetteConfig;
 use crate::structs::config::global_config::GlobalConfig;
+use crate::structs::config::http_config::HttpConfig;
+use crate::structs::config::model_pricing::ModelPricing;
 use crate::structs::config::notification_config::NotificationConfig;
 use crate::structs::config::output_config::OutputConfig;
+use crate::structs::config::profile_config::ProfileConfig;
+use crate::structs::config::prompt_config::PromptConfig;
 use crate::structs::config::repository_config::RepositoryConfig;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -17,6 +30,30 @@ pub struct Config {
 
     #[serde(default)]
     pub notifications: NotificationConfig,
+
+    #[serde(default)]
+    pub ai: AiConfig,
+
+    #[serde(default)]
+    pub analysis: AnalysisConfig,
+
+    #[serde(default)]
+    pub pricing: HashMap<String, ModelPricing>,
+
+    #[serde(default)]
+    pub budget: BudgetConfig,
+
+    #[serde(default)]
+    pub cassette: CassetteConfig,
+
+    #[serde(default)]
+    pub http: HttpConfig,
+
+    #[serde(default)]
+    pub prompts: PromptConfig,
+
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
 }
 
 impl Default for Config {
@@ -26,6 +63,151 @@ impl Default for Config {
             repositories: vec![],
             output: OutputConfig::default(),
             notifications: Default::default(),
+            ai: AiConfig::default(),
+            analysis: AnalysisConfig::default(),
+            pricing: HashMap::new(),
+            budget: BudgetConfig::default(),
+            cassette: CassetteConfig::default(),
+            http: HttpConfig::default(),
+            prompts: PromptConfig::default(),
+            profiles: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// A `[repositories.ai]` table holds the global `[ai]` settings it leaves out, merged in
+    /// when the configuration is loaded.
+    pub fn ai_config_for(&self, repository_config: &RepositoryConfig) -> AiConfig {
+        repository_config.ai.clone().unwrap_or_else(|| self.ai.clone())
+    }
+
+    /// Like `ai_config_for`, a `[repositories.analysis]` table holds the `[analysis]` settings
+    /// it leaves out.
+    pub fn analysis_config_for(&self, repository_config: &RepositoryConfig) -> AnalysisConfig {
+        repository_config.analysis.clone().unwrap_or_else(|| self.analysis.clone())
+    }
+
+    /// Like `ai_config_for`, a `[repositories.prompts]` table holds the `[prompts]` settings
+    /// it leaves out.
+    pub fn prompt_config_for(&self, repository_config: &RepositoryConfig) -> PromptConfig {
+        repository_config.prompts.clone().unwrap_or_else(|| self.prompts.clone())
+    }
+
+    /// `--categories` on the command line replaces the configured categories everywhere.
+    pub fn override_categories(&mut self, categories: Vec<String>) {
+        for repo in self.repositories.iter_mut() {
+            if let Some(analysis) = repo.analysis.as_mut() {
+                analysis.categories = categories.clone();
+            }
         }
+        self.analysis.categories = categories;
     }
-}
\ No newline at end of file
+
+    /// Applies the `[profiles.<name>]` sections on top of the configuration. The keys a
+    /// profile section sets win over both the global and the repository-level section; the
+    /// rest is kept.
+    pub fn apply_profile(&mut self, name: &str) -> AicedResult<()> {
+        let profile = self.profiles.get(name).cloned().ok_or_else(|| {
+            let mut known: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+            known.sort();
+            AicedError::config_error(
+                &format!("Unknown profile '{}'", name),
+                Some("profiles"),
+                Some(&format!("Known profiles: {}", if known.is_empty() { "none".to_string() } else { known.join(", ") })),
+            )
+        })?;
+
+        self.merge_profile(&profile)?;
+        log::info!("🎛️ Using profile '{}'", name);
+        Ok(())
+    }
+
+    /// Merges the sections of `profile` over the global sections and every repository
+    /// override, the same way repository tables inherit from the global ones.
+    pub fn merge_profile(&mut self, profile: &ProfileConfig) -> AicedResult<()> {
+        if let Some(analysis) = &profile.analysis {
+            self.analysis = Self::merge_over(&self.analysis, analysis, ConfigHelper::merge_missing)?;
+            for repo in self.repositories.iter_mut() {
+                if let Some(repo_analysis) = &repo.analysis {
+                    repo.analysis = Some(Self::merge_over(repo_analysis, analysis, ConfigHelper::merge_missing)?);
+                }
+            }
+        }
+        if let Some(prompts) = &profile.prompts {
+            self.prompts = Self::merge_over(&self.prompts, prompts, ConfigManager::inherit_prompts)?;
+            for repo in self.repositories.iter_mut() {
+                if let Some(repo_prompts) = &repo.prompts {
+                    repo.prompts = Some(Self::merge_over(repo_prompts, prompts, ConfigManager::inherit_prompts)?);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `section` with the keys of `overrides` replaced, `inherit` filling in the rest.
+    fn merge_over<T: Serialize + DeserializeOwned>(section: &T, overrides: &toml::Table, inherit: fn(&mut toml::Table, &toml::Table)) -> AicedResult<T> {
+        let parent = toml::Table::try_from(section)
+            .map_err(|e| AicedError::system_error("serialize_profile_section", &e.to_string()))?;
+        let mut merged = overrides.clone();
+        inherit(&mut merged, &parent);
+        Ok(merged.try_into()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+
+    fn profiled(config: &str) -> Config {
+        let mut config: Config = toml::from_str(config).unwrap();
+        config.apply_profile("release").unwrap();
+        config
+    }
+
+    #[test]
+    fn profile_keys_override_global_and_repository_sections() {
+        let config = profiled(r#"
+            [analysis]
+            max_files = 20
+            focus_areas = ["PERFORMANCE"]
+
+            [[repositories]]
+            name = "a"
+            path = "/tmp/a"
+            [repositories.analysis]
+            max_files = 5
+
+            [profiles.release.analysis]
+            focus_areas = ["SECURITY"]
+        "#);
+        let repo_analysis = config.repositories[0].analysis.as_ref().unwrap();
+
+        assert_eq!(config.analysis.focus_areas, vec!["SECURITY"]);
+        assert_eq!(config.analysis.max_files, 20);
+        assert_eq!(repo_analysis.focus_areas, vec!["SECURITY"]);
+        assert_eq!(repo_analysis.max_files, 5);
+    }
+
+    #[test]
+    fn profile_guidelines_replace_a_guidelines_file() {
+        let config = profiled(r#"
+            [prompts]
+            language = "German"
+            guidelines_file = "~/aiced/guidelines.md"
+
+            [profiles.release.prompts]
+            guidelines = "Only release blockers."
+        "#);
+
+        assert_eq!(config.prompts.language, "German");
+        assert_eq!(config.prompts.guidelines.as_deref(), Some("Only release blockers."));
+        assert_eq!(config.prompts.guidelines_file, None);
+    }
+
+    #[test]
+    fn unknown_profile_is_an_error() {
+        let mut config: Config = toml::from_str("").unwrap();
+        assert!(config.apply_profile("release").is_err());
+    }
+}
@@ -12,4 +12,10 @@ pub mod webhook_config;
 pub mod security_config;
 pub mod output_config;
 pub mod performance_config;
-pub mod style_config;
\ No newline at end of file
+pub mod style_config;
+pub mod ai_config;
+pub mod model_pricing;
+pub mod budget_config;
+pub mod cassette_config;
+pub mod http_config;
+pub mod prompt_config;
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use crate::structs::usage::token_usage::TokenUsage;
+
+/// Price of a model in the configured currency per million tokens. Cache prices default
+/// to Anthropic's multipliers of the input price (1.25x for writes, 0.1x for reads).
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+    #[serde(default)]
+    pub cache_write_per_million: Option<f64>,
+    #[serde(default)]
+    pub cache_read_per_million: Option<f64>,
+}
+
+impl ModelPricing {
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        let cache_write = self.cache_write_per_million.unwrap_or(self.input_per_million * 1.25);
+        let cache_read = self.cache_read_per_million.unwrap_or(self.input_per_million * 0.1);
+
+        (usage.input_tokens as f64 * self.input_per_million
+            + usage.output_tokens as f64 * self.output_per_million
+            + usage.cache_creation_tokens as f64 * cache_write
+            + usage.cache_read_tokens as f64 * cache_read) / 1_000_000.0
+    }
+}
@@ -1,12 +1,16 @@
 use serde::{Deserialize, Serialize};
-use crate::structs::config::analysis_config::AnalysisConfig;
 use crate::structs::config::security_config::SecurityConfig;
 
+/// A named set of overrides selected with `--profile`. Its sections stay raw tables so that
+/// only the keys they set override the global and repository ones.
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ProfileConfig {
     #[serde(default)]
-    pub analysis: AnalysisConfig,
+    pub analysis: Option<toml::Table>,
+
+    #[serde(default)]
+    pub prompts: Option<toml::Table>,
 
     #[serde(default)]
     pub security: SecurityConfig,
-}
\ No newline at end of file
+}
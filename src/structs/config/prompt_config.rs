@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use crate::helpers::config_helper::ConfigHelper;
+
+/// Prompt templates and the values of their variables. Templates default to the built-in prompts.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PromptConfig {
+    /// System prompt for the text output protocol.
+    #[serde(default)]
+    pub analysis_template: Option<String>,
+
+    /// System prompt for the tool output mode.
+    #[serde(default)]
+    pub tools_template: Option<String>,
+
+    /// User prompt around the file listing.
+    #[serde(default)]
+    pub user_template: Option<String>,
+
+    /// Language of the reasons and the summary.
+    #[serde(default = "ConfigHelper::default_prompt_language")]
+    pub language: String,
+
+    #[serde(default)]
+    pub guidelines: Option<String>,
+
+    #[serde(default)]
+    pub guidelines_file: Option<String>,
+}
+
+impl PromptConfig {
+    fn template_paths(&self) -> Vec<(&'static str, PathBuf)> {
+        [
+            ("prompts.analysis_template", &self.analysis_template),
+            ("prompts.tools_template", &self.tools_template),
+            ("prompts.user_template", &self.user_template),
+            ("prompts.guidelines_file", &self.guidelines_file),
+        ]
+        .into_iter()
+        .filter_map(|(field, path)| path.as_deref().map(|path| (field, ConfigHelper::expand_home(path))))
+        .collect()
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors: Vec<String> = self.template_paths()
+            .into_iter()
+            .filter(|(_, path)| !path.is_file())
+            .map(|(field, path)| format!("{} does not exist: {}", field, path.display()))
+            .collect();
+
+        if self.guidelines.is_some() && self.guidelines_file.is_some() {
+            errors.push("prompts.guidelines and prompts.guidelines_file cannot both be set".to_string());
+        }
+        if self.language.trim().is_empty() {
+            errors.push("prompts.language cannot be empty".to_string());
+        }
+
+        errors
+    }
+}
+
+impl Default for PromptConfig {
+    fn default() -> Self {
+        Self {
+            analysis_template: None,
+            tools_template: None,
+            user_template: None,
+            language: ConfigHelper::default_prompt_language(),
+            guidelines: None,
+            guidelines_file: None,
+        }
+    }
+}
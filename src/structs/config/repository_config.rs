@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use crate::structs::config::ai_config::AiConfig;
+use crate::structs::config::analysis_config::AnalysisConfig;
+use crate::structs::config::prompt_config::PromptConfig;
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct RepositoryConfig {
@@ -14,4 +17,13 @@ pub struct RepositoryConfig {
 
     #[serde(default)]
     pub auto_pr: bool,
+
+    #[serde(default)]
+    pub ai: Option<AiConfig>,
+
+    #[serde(default)]
+    pub analysis: Option<AnalysisConfig>,
+
+    #[serde(default)]
+    pub prompts: Option<PromptConfig>,
 }
\ No newline at end of file
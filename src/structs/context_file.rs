@@ -0,0 +1,11 @@
+use crate::enums::context_file_kind::ContextFileKind;
+
+/// A manifest, lockfile summary or CI/container config sent with every analysis prompt.
+/// Context files are never editable.
+#[derive(Clone)]
+pub struct ContextFile {
+    /// Path relative to the repository root.
+    pub path: String,
+    pub kind: ContextFileKind,
+    pub content: String,
+}
@@ -0,0 +1,41 @@
+use std::time::Instant;
+use crate::structs::stream_progress::StreamProgress;
+
+/// Progress across the requests of one conversation, so that continuations and tool rounds
+/// add to the totals of the requests before them instead of starting over.
+pub struct ConversationProgress {
+    started: Instant,
+    finished: Option<StreamProgress>,
+}
+
+impl ConversationProgress {
+    pub fn start() -> Self {
+        Self { started: Instant::now(), finished: None }
+    }
+
+    pub fn started(&self) -> Instant {
+        self.started
+    }
+
+    /// The conversation so far, with `current` as the progress of the request in flight.
+    pub fn with(&self, current: StreamProgress) -> StreamProgress {
+        let Some(finished) = &self.finished else {
+            return current;
+        };
+
+        StreamProgress {
+            input_tokens: finished.input_tokens + current.input_tokens,
+            output_tokens: finished.output_tokens + current.output_tokens,
+            estimated_cost: finished.estimated_cost + current.estimated_cost,
+            changes_seen: finished.changes_seen + current.changes_seen,
+            ..current
+        }
+    }
+
+    /// Records the last progress of a request that ended as the new baseline.
+    pub fn finish_request(&mut self, last: Option<StreamProgress>) {
+        if last.is_some() {
+            self.finished = last;
+        }
+    }
+}
@@ -9,4 +9,7 @@ pub struct ChangeItem {
     pub new_content: Option<String>,
     pub applied: bool,
     pub reason: String,
+    /// The model's thinking about this file, when it was captured.
+    #[serde(default)]
+    pub rationale: Option<String>,
 }
\ No newline at end of file
@@ -9,6 +9,9 @@ pub struct DiffSession {
     pub repository_name: String,
     pub repository_path: String,
     pub files: Vec<FileDiff>,
+    /// The model's full thinking for the analysis, empty when none was captured.
+    #[serde(default)]
+    pub thinking: String,
     pub applied_changes: HashSet<String>,
     pub status: SessionStatus,
 }
\ No newline at end of file
@@ -1,4 +1,7 @@
+#[derive(Clone)]
 pub struct FileInfo {
     pub path: String,
     pub content: String,
+    /// Unchanged code sent along for context; no changes are accepted for it.
+    pub read_only: bool,
 }
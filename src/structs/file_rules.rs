@@ -0,0 +1,178 @@
+use std::path::{Path, PathBuf};
+use crate::config::constants::{SUPPORTED_FILE_EXTENSIONS, TEST_DIRS, TEST_FILE_PATTERNS};
+use crate::helpers::gitignore;
+use crate::enums::skip_reason::SkipReason;
+use crate::structs::config::analysis_config::AnalysisConfig;
+use crate::structs::pattern_set::PatternSet;
+use crate::structs::scan_report::ScanReport;
+
+/// The deterministic file selection: `include_patterns`, `exclude_patterns`, `languages`,
+/// `file_extensions` and `skip_tests` from `[analysis]`.
+#[derive(Debug, Clone)]
+pub struct FileRules {
+    include: PatternSet,
+    exclude: PatternSet,
+    languages: Vec<String>,
+    extensions: Vec<String>,
+    skip_tests: bool,
+}
+
+impl FileRules {
+    pub fn from_config(config: &AnalysisConfig) -> Self {
+        Self {
+            include: PatternSet::new(&config.include_patterns, "analysis.include_patterns"),
+            exclude: PatternSet::new(&config.exclude_patterns, "analysis.exclude_patterns"),
+            languages: config.languages.iter().map(|language| language.to_lowercase()).collect(),
+            extensions: config.file_extensions
+                .iter()
+                .map(|ext| ext.trim_start_matches('.').to_lowercase())
+                .collect(),
+            skip_tests: config.skip_tests,
+        }
+    }
+
+    /// The language of a file by its extension, as listed in `SUPPORTED_FILE_EXTENSIONS`.
+    pub fn language_of(path: &Path) -> Option<&'static str> {
+        let ext = path.extension()?.to_str()?;
+        SUPPORTED_FILE_EXTENSIONS
+            .iter()
+            .find(|(known, _)| known.eq_ignore_ascii_case(ext))
+            .map(|(_, language)| *language)
+    }
+
+    /// Whether the file is a test: it sits in a test directory, or its name follows the
+    /// test naming convention of its language. Gives the directory or pattern that decides.
+    fn test_rule(relative_path: &str) -> Option<String> {
+        let mut components: Vec<&str> = relative_path.split('/').collect();
+        let file_name = components.pop().unwrap_or_default();
+        if let Some(dir) = components.iter().find(|dir| TEST_DIRS.contains(dir)) {
+            return Some(format!("{}/", dir));
+        }
+
+        let language = Self::language_of(Path::new(file_name))?;
+        TEST_FILE_PATTERNS
+            .iter()
+            .filter(|(test_language, _)| *test_language == language)
+            .flat_map(|(_, patterns)| patterns.iter())
+            .find(|pattern| gitignore::wildmatch(pattern, file_name))
+            .map(|pattern| pattern.to_string())
+    }
+
+    /// The rule that leaves a file out, e.g. `analysis.exclude_patterns[0] (target/)`, or
+    /// `None` when the rules select it. The file is given by its path relative to the
+    /// repository root. Files in one of `languages` are selected, as are files with one of
+    /// `file_extensions`; with both lists empty the language does not matter.
+    pub fn rejection(&self, relative_path: &str) -> Option<String> {
+        if !self.include.is_empty() && !self.include.matches_file(relative_path) {
+            return Some("analysis.include_patterns (no pattern matches)".to_string());
+        }
+        if let Some(rule) = self.exclude.matching_rule(relative_path) {
+            return Some(format!("{} ({})", rule.source, rule.pattern));
+        }
+
+        let path = Path::new(relative_path);
+        if !self.languages.is_empty() || !self.extensions.is_empty() {
+            let in_language = Self::language_of(path).is_some_and(|language| self.languages.iter().any(|l| l == language));
+            let has_extension = path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| self.extensions.iter().any(|known| known.eq_ignore_ascii_case(ext)));
+            if !in_language && !has_extension {
+                return Some("analysis.languages and analysis.file_extensions (not listed)".to_string());
+            }
+        }
+
+        if !self.skip_tests {
+            return None;
+        }
+        Self::test_rule(relative_path).map(|rule| format!("analysis.skip_tests (test rule {})", rule))
+    }
+
+    /// The files the rules select, in their original order. The others go into `report`
+    /// with the rule that left them out.
+    pub fn select(&self, paths: Vec<PathBuf>, repo_path: &str, report: &mut ScanReport) -> Vec<PathBuf> {
+        paths
+            .into_iter()
+            .filter(|path| {
+                let relative = path.strip_prefix(repo_path).unwrap_or(path).to_string_lossy();
+                match self.rejection(&relative) {
+                    Some(rule) => {
+                        report.skip(path, repo_path, SkipReason::Rules(rule));
+                        false
+                    }
+                    None => true,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FileRules;
+    use crate::structs::config::analysis_config::AnalysisConfig;
+
+    fn rules(analysis: &str) -> FileRules {
+        let config: AnalysisConfig = toml::from_str(analysis).unwrap();
+        FileRules::from_config(&config)
+    }
+
+    #[test]
+    fn include_patterns_are_checked_before_exclude_patterns() {
+        let rules = rules(r#"
+            include_patterns = ["src/"]
+            exclude_patterns = ["generated/", "*.min.js"]
+            languages = []
+        "#);
+
+        assert_eq!(rules.rejection("docs/a.min.js").as_deref(), Some("analysis.include_patterns (no pattern matches)"));
+        assert_eq!(rules.rejection("src/app.min.js").as_deref(), Some("analysis.exclude_patterns[1] (*.min.js)"));
+        assert_eq!(rules.rejection("src/generated/api.rs").as_deref(), Some("analysis.exclude_patterns[0] (generated/)"));
+        assert_eq!(rules.rejection("src/main.rs"), None);
+    }
+
+    #[test]
+    fn languages_and_extensions_both_select_files() {
+        let rules = rules(r#"
+            include_patterns = []
+            exclude_patterns = []
+            languages = ["Rust"]
+            file_extensions = [".toml"]
+        "#);
+
+        assert_eq!(rules.rejection("src/main.rs"), None);
+        assert_eq!(rules.rejection("Cargo.TOML"), None);
+        assert_eq!(rules.rejection("web/app.ts").as_deref(), Some("analysis.languages and analysis.file_extensions (not listed)"));
+    }
+
+    #[test]
+    fn skip_tests_comes_last() {
+        let rules = rules(r#"
+            include_patterns = []
+            exclude_patterns = ["*.py"]
+            languages = []
+            skip_tests = true
+        "#);
+
+        assert_eq!(rules.rejection("test_app.py").as_deref(), Some("analysis.exclude_patterns[0] (*.py)"));
+        assert_eq!(rules.rejection("src/app_test.go").as_deref(), Some("analysis.skip_tests (test rule *_test.go)"));
+    }
+
+    #[test]
+    fn test_files_are_found_by_directory_or_name() {
+        assert_eq!(FileRules::test_rule("src/__tests__/app.js").as_deref(), Some("__tests__/"));
+        assert_eq!(FileRules::test_rule("pkg/server_test.go").as_deref(), Some("*_test.go"));
+        assert_eq!(FileRules::test_rule("app/test_views.py").as_deref(), Some("test_*.py"));
+        assert_eq!(FileRules::test_rule("src/UserServiceTest.java").as_deref(), Some("*Test.java"));
+        assert_eq!(FileRules::test_rule("web/button.spec.tsx").as_deref(), Some("*.spec.tsx"));
+    }
+
+    #[test]
+    fn similar_names_are_not_tests() {
+        assert_eq!(FileRules::test_rule("src/contest.py"), None);
+        assert_eq!(FileRules::test_rule("src/latest/main.rs"), None);
+        assert_eq!(FileRules::test_rule("src/testing.go"), None);
+        // Test patterns only apply to the language they are listed for.
+        assert_eq!(FileRules::test_rule("docs/api_test.md"), None);
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use crate::config::constants::{AICEDIGNORE_FILE, DEFAULT_IGNORE_PATTERNS, GITIGNORE_FILE};
+use crate::helpers::git_helper;
+use crate::structs::ignore_rule::IgnoreRule;
+
+/// The ignore rules of a repository. Git's own rules apply as git applies them: the global
+/// excludes file, then `.git/info/exclude`, then the `.gitignore` files from the root down,
+/// with the last matching rule winning. `.aicedignore` files are layered on top of all of
+/// them, and the built-in defaults sit below.
+pub struct IgnoreMatcher {
+    repo_path: PathBuf,
+    git_dir: IgnoreRule,
+    defaults: Vec<IgnoreRule>,
+    git_rules: Vec<IgnoreRule>,
+    aiced_rules: Vec<IgnoreRule>,
+    loaded_dirs: HashSet<String>,
+}
+
+impl IgnoreMatcher {
+    /// Loads the repository wide rules and the ignore files of the root directory. Nested
+    /// ignore files are loaded with `load_dir` while walking.
+    pub fn for_repo(repo_path: &str) -> Self {
+        let mut matcher = Self {
+            repo_path: PathBuf::from(repo_path),
+            git_dir: IgnoreRule::parse(".git", "", "built-in".to_string()).expect("valid built-in rule"),
+            defaults: DEFAULT_IGNORE_PATTERNS
+                .iter()
+                .filter_map(|pattern| IgnoreRule::parse(pattern, "", "built-in".to_string()))
+                .collect(),
+            git_rules: Vec::new(),
+            aiced_rules: Vec::new(),
+            loaded_dirs: HashSet::new(),
+        };
+
+        if let Some(global) = git_helper::global_excludes_file(repo_path) {
+            let rules = Self::read_rules(&global, "", &global.to_string_lossy());
+            matcher.git_rules.extend(rules);
+        }
+        let info_exclude = matcher.repo_path.join(".git").join("info").join("exclude");
+        matcher.git_rules.extend(Self::read_rules(&info_exclude, "", ".git/info/exclude"));
+        matcher.load_dir("");
+        matcher
+    }
+
+    /// Loads the `.gitignore` and `.aicedignore` of a directory relative to the repository
+    /// root. A directory is loaded once; parents must be loaded before their children.
+    pub fn load_dir(&mut self, relative_dir: &str) {
+        let relative_dir = relative_dir.trim_matches('/');
+        if !self.loaded_dirs.insert(relative_dir.to_string()) {
+            return;
+        }
+
+        let dir = self.repo_path.join(relative_dir);
+        let source = |name: &str| match relative_dir {
+            "" => name.to_string(),
+            _ => format!("{}/{}", relative_dir, name),
+        };
+        let git_rules = Self::read_rules(&dir.join(GITIGNORE_FILE), relative_dir, &source(GITIGNORE_FILE));
+        let aiced_rules = Self::read_rules(&dir.join(AICEDIGNORE_FILE), relative_dir, &source(AICEDIGNORE_FILE));
+        self.git_rules.extend(git_rules);
+        self.aiced_rules.extend(aiced_rules);
+    }
+
+    fn read_rules(path: &Path, base: &str, source: &str) -> Vec<IgnoreRule> {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return Vec::new();
+        };
+
+        content
+            .lines()
+            .enumerate()
+            .filter_map(|(index, line)| IgnoreRule::parse(line, base, format!("{}:{}", source, index + 1)))
+            .collect()
+    }
+
+    /// The rule that decides about the path itself, if any. Parent directories are not
+    /// considered; the walk never enters an ignored directory.
+    pub fn decide(&self, relative_path: &str, is_dir: bool) -> Option<&IgnoreRule> {
+        if self.git_dir.matches(relative_path, is_dir) {
+            return Some(&self.git_dir);
+        }
+
+        [&self.aiced_rules, &self.git_rules, &self.defaults]
+            .into_iter()
+            .find_map(|rules| rules.iter().rev().find(|rule| rule.matches(relative_path, is_dir)))
+    }
+
+    pub fn is_ignored(&self, relative_path: &str, is_dir: bool) -> bool {
+        self.decide(relative_path, is_dir).is_some_and(|rule| !rule.is_negated())
+    }
+
+    /// The path that decided about `relative_path` with its rule: the first ignored parent
+    /// directory, since nothing inside it can be re-included, or else the path itself.
+    pub fn explain(&mut self, relative_path: &str, is_dir: bool) -> Option<(String, &IgnoreRule)> {
+        let components: Vec<&str> = relative_path.split('/').collect();
+        let parents: Vec<String> = (1..components.len()).map(|depth| components[..depth].join("/")).collect();
+        for parent in &parents {
+            self.load_dir(parent);
+        }
+
+        match parents.into_iter().find(|parent| self.is_ignored(parent, true)) {
+            Some(parent) => self.decide(&parent, true).map(|rule| (parent, rule)),
+            None => self.decide(relative_path, is_dir).map(|rule| (relative_path.to_string(), rule)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use super::IgnoreMatcher;
+
+    fn repo(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, content) in files {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    fn matcher(dir: &tempfile::TempDir, loaded: &[&str]) -> IgnoreMatcher {
+        let mut matcher = IgnoreMatcher::for_repo(dir.path().to_str().unwrap());
+        for relative_dir in loaded {
+            matcher.load_dir(relative_dir);
+        }
+        matcher
+    }
+
+    #[test]
+    fn anchored_patterns_apply_to_the_directory_of_their_file() {
+        let dir = repo(&[(".gitignore", "/build\ndocs/*.md\n"), ("src/.gitignore", "/gen.rs\n")]);
+        let matcher = matcher(&dir, &["src"]);
+
+        assert!(matcher.is_ignored("build", true));
+        assert!(!matcher.is_ignored("src/build", true));
+        assert!(matcher.is_ignored("docs/a.md", false));
+        assert!(!matcher.is_ignored("docs/sub/a.md", false));
+        assert!(matcher.is_ignored("src/gen.rs", false));
+        assert!(!matcher.is_ignored("gen.rs", false));
+        assert!(!matcher.is_ignored("src/sub/gen.rs", false));
+    }
+
+    #[test]
+    fn unanchored_and_directory_only_patterns() {
+        let dir = repo(&[(".gitignore", "*.log\nout/\n")]);
+        let matcher = matcher(&dir, &[]);
+
+        assert!(matcher.is_ignored("a/b/debug.log", false));
+        assert!(matcher.is_ignored("a/out", true));
+        assert!(!matcher.is_ignored("a/out", false));
+    }
+
+    #[test]
+    fn negation_and_last_match_wins() {
+        let dir = repo(&[(".gitignore", "*.log\n!keep.log\n")]);
+        let matcher = matcher(&dir, &[]);
+
+        assert!(matcher.is_ignored("debug.log", false));
+        assert!(!matcher.is_ignored("keep.log", false));
+        assert!(matcher.decide("keep.log", false).is_some_and(|rule| rule.is_negated()));
+    }
+
+    #[test]
+    fn nested_files_override_their_parents() {
+        let dir = repo(&[(".gitignore", "*.json\n"), ("config/.gitignore", "!*.json\n")]);
+        let matcher = matcher(&dir, &["config"]);
+
+        assert!(matcher.is_ignored("data.json", false));
+        assert!(!matcher.is_ignored("config/app.json", false));
+    }
+
+    #[test]
+    fn aicedignore_takes_precedence_over_git() {
+        let dir = repo(&[(".gitignore", "!fixtures/\n"), (".aicedignore", "fixtures/\n!*.svg\n")]);
+        let matcher = matcher(&dir, &[]);
+
+        assert!(matcher.is_ignored("fixtures", true));
+        // A negated .aicedignore rule re-includes what the built-in defaults leave out.
+        assert!(!matcher.is_ignored("logo.svg", false));
+    }
+
+    #[test]
+    fn git_rules_can_re_include_defaults_but_not_the_git_dir() {
+        let dir = repo(&[(".gitignore", "!*.png\n!.git\n")]);
+        let matcher = matcher(&dir, &[]);
+
+        assert!(!matcher.is_ignored("logo.png", false));
+        assert!(matcher.is_ignored("logo.jpg", false));
+        assert!(matcher.is_ignored(".git", true));
+    }
+
+    #[test]
+    fn explain_reports_the_ignored_parent() {
+        let dir = repo(&[(".gitignore", "build/\n!build/keep.js\n")]);
+        let mut matcher = matcher(&dir, &[]);
+
+        let (path, rule) = matcher.explain("build/keep.js", false).unwrap();
+        assert_eq!(path, "build");
+        assert_eq!(rule.source, ".gitignore:1");
+        assert!(matcher.explain("src/main.rs", false).is_none());
+    }
+}
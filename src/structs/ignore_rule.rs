@@ -0,0 +1,81 @@
+use crate::helpers::gitignore;
+
+/// One pattern line of an ignore file, scoped to the directory that holds the file.
+#[derive(Debug, Clone)]
+pub struct IgnoreRule {
+    /// The line as written, e.g. `!/build/keep.txt`.
+    pub pattern: String,
+    /// Where the rule comes from, e.g. `src/.gitignore:3`.
+    pub source: String,
+    base: String,
+    glob: String,
+    negated: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+impl IgnoreRule {
+    /// Parses a line of an ignore file in `base`, the directory relative to the repository
+    /// root ("" for the root). Blank lines and comments give `None`.
+    pub fn parse(line: &str, base: &str, source: String) -> Option<Self> {
+        let mut line = line.trim_end_matches(['\r', '\n']);
+        while line.ends_with(' ') && !line.ends_with("\\ ") {
+            line = &line[..line.len() - 1];
+        }
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let pattern = line.to_string();
+        let negated = line.starts_with('!');
+        let mut glob = if negated { &line[1..] } else { line };
+        let dir_only = glob.ends_with('/');
+        if dir_only {
+            glob = &glob[..glob.len() - 1];
+        }
+        // A slash anywhere but at the end ties the pattern to the directory of the file.
+        let anchored = glob.contains('/');
+        let glob = glob.strip_prefix('/').unwrap_or(glob);
+        if glob.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            pattern,
+            source,
+            base: base.trim_matches('/').to_string(),
+            glob: glob.to_string(),
+            negated,
+            dir_only,
+            anchored,
+        })
+    }
+
+    /// Whether a match re-includes the path instead of ignoring it.
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    /// Whether the rule matches `relative_path`, a `/` separated path relative to the
+    /// repository root.
+    pub fn matches(&self, relative_path: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        let path = if self.base.is_empty() {
+            relative_path
+        } else {
+            match relative_path.strip_prefix(&self.base).and_then(|rest| rest.strip_prefix('/')) {
+                Some(rest) => rest,
+                None => return false,
+            }
+        };
+
+        if self.anchored {
+            gitignore::wildmatch(&self.glob, path)
+        } else {
+            gitignore::wildmatch(&self.glob, path.rsplit('/').next().unwrap_or(path))
+        }
+    }
+}
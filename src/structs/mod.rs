@@ -12,4 +12,15 @@ pub mod category_stats;
 pub mod diff;
 pub mod ai;
 pub mod stream_item;
-pub mod stream_result;
\ No newline at end of file
+pub mod stream_result;
+pub mod usage;
+pub mod stream_progress;
+pub mod context_file;
+pub mod change_filter;
+pub mod scan_report;
+pub mod ignore_rule;
+pub mod ignore_matcher;
+pub mod pattern_set;
+pub mod file_rules;
+pub mod batch_plan;
+pub mod conversation_progress;
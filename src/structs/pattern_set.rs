@@ -0,0 +1,84 @@
+use crate::structs::ignore_rule::IgnoreRule;
+
+/// Gitignore-style patterns from the configuration, such as `include_patterns`.
+#[derive(Debug, Clone)]
+pub struct PatternSet {
+    rules: Vec<IgnoreRule>,
+}
+
+impl PatternSet {
+    /// `source` names the setting in explanations, e.g. `analysis.exclude_patterns`.
+    pub fn new(patterns: &[String], source: &str) -> Self {
+        let rules = patterns
+            .iter()
+            .enumerate()
+            .filter_map(|(index, pattern)| IgnoreRule::parse(pattern, "", format!("{}[{}]", source, index)))
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether the file or one of its parent directories matches. As in a `.gitignore`, the
+    /// last matching pattern wins and `!` patterns take a path back out.
+    pub fn matches_file(&self, relative_path: &str) -> bool {
+        self.matching_rule(relative_path).is_some()
+    }
+
+    /// The pattern that makes the file or one of its parent directories match.
+    pub fn matching_rule(&self, relative_path: &str) -> Option<&IgnoreRule> {
+        let components: Vec<&str> = relative_path.split('/').collect();
+        (1..components.len())
+            .find_map(|depth| self.decides(&components[..depth].join("/"), true))
+            .or_else(|| self.decides(relative_path, false))
+    }
+
+    fn decides(&self, relative_path: &str, is_dir: bool) -> Option<&IgnoreRule> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(relative_path, is_dir))
+            .filter(|rule| !rule.is_negated())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PatternSet;
+
+    fn patterns(patterns: &[&str]) -> PatternSet {
+        let patterns: Vec<String> = patterns.iter().map(|pattern| pattern.to_string()).collect();
+        PatternSet::new(&patterns, "analysis.exclude_patterns")
+    }
+
+    #[test]
+    fn the_last_matching_pattern_wins() {
+        let set = patterns(&["*.rs", "!main.rs", "src/bin/*.rs"]);
+        assert!(set.matches_file("src/lib.rs"));
+        assert!(!set.matches_file("src/main.rs"));
+        assert!(set.matches_file("src/bin/main.rs"));
+    }
+
+    #[test]
+    fn directory_patterns_match_the_files_below() {
+        let set = patterns(&["docs/", "target/"]);
+        let rule = set.matching_rule("crates/x/target/debug/build.rs").unwrap();
+        assert_eq!(rule.pattern, "target/");
+        assert_eq!(rule.source, "analysis.exclude_patterns[1]");
+        assert!(!set.matches_file("src/target"));
+    }
+
+    #[test]
+    fn anchored_patterns_match_from_the_root_only() {
+        let set = patterns(&["/build"]);
+        assert!(set.matches_file("build/out.js"));
+        assert!(!set.matches_file("web/build/out.js"));
+    }
+
+    #[test]
+    fn comments_and_blank_lines_are_no_patterns() {
+        assert!(patterns(&["# vendored", ""]).is_empty());
+    }
+}
@@ -0,0 +1,27 @@
+use std::path::Path;
+use crate::enums::skip_reason::SkipReason;
+
+/// Files the scanner or the batching left out, with the reason, logged before the analysis.
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    pub skipped: Vec<(String, SkipReason)>,
+}
+
+impl ScanReport {
+    pub fn skip(&mut self, path: &Path, repo_path: &str, reason: SkipReason) {
+        let relative = path.strip_prefix(repo_path).unwrap_or(path).to_string_lossy().to_string();
+        self.skipped.push((relative, reason));
+    }
+
+    pub fn log(&self, selected: usize) {
+        if self.skipped.is_empty() {
+            log::info!("📋 Scan selected {} files", selected);
+            return;
+        }
+
+        log::warn!("📋 Scan selected {} files, skipped {}:", selected, self.skipped.len());
+        for (path, reason) in &self.skipped {
+            log::warn!("   ⏭️ {}: {}", path, reason);
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use serde::{Deserialize, Serialize};
+use crate::structs::ai::serving_provider::ServingProvider;
+use crate::structs::ai::tool_call::ToolCall;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StreamItem {
@@ -7,6 +9,24 @@ pub struct StreamItem {
     pub stop_reason: Option<String>,
     pub input_tokens: Option<u32>,
     pub output_tokens: Option<u32>,
+    /// Set when the provider restarted the request after a mid-stream failure:
+    /// everything received before this item must be discarded.
+    #[serde(default)]
+    pub is_restart: bool,
+    /// Extended-thinking text, streamed separately from the answer.
+    #[serde(default)]
+    pub thinking: String,
+    #[serde(default)]
+    pub cache_creation_tokens: Option<u32>,
+    #[serde(default)]
+    pub cache_read_tokens: Option<u32>,
+    /// A tool invocation whose arguments finished streaming.
+    #[serde(default)]
+    pub tool_call: Option<ToolCall>,
+    /// Set by providers that switch between providers, such as a fallback chain; on a
+    /// restart item it names the provider that failed.
+    #[serde(default)]
+    pub served_by: Option<ServingProvider>,
 }
 
 impl StreamItem {
@@ -17,6 +37,33 @@ impl StreamItem {
             stop_reason: None,
             input_tokens: None,
             output_tokens: None,
+            is_restart: false,
+            thinking: String::new(),
+            cache_creation_tokens: None,
+            cache_read_tokens: None,
+            tool_call: None,
+            served_by: None,
+        }
+    }
+
+    pub fn thinking(thinking: String) -> Self {
+        Self {
+            thinking,
+            ..Self::new(String::new())
+        }
+    }
+
+    pub fn tool_call(tool_call: ToolCall) -> Self {
+        Self {
+            tool_call: Some(tool_call),
+            ..Self::new(String::new())
+        }
+    }
+
+    pub fn restart() -> Self {
+        Self {
+            is_restart: true,
+            ..Self::new(String::new())
         }
     }
 
@@ -27,16 +74,16 @@ impl StreamItem {
             stop_reason,
             input_tokens: Some(0),
             output_tokens: Some(output_tokens),
+            ..Self::new(String::new())
         }
     }
 
     pub fn with_tokens(content: String, input_tokens: Option<u32>, output_tokens: Option<u32>) -> Self {
         Self {
             content,
-            is_complete: false,
-            stop_reason: None,
             input_tokens,
             output_tokens,
+            ..Self::new(String::new())
         }
     }
 }
\ No newline at end of file
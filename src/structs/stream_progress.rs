@@ -0,0 +1,28 @@
+use std::time::Duration;
+use crate::enums::ai_phase::AiPhase;
+
+/// Snapshot of a response that is still streaming, published by `AicedAdapter`.
+#[derive(Debug, Clone)]
+pub struct StreamProgress {
+    pub phase: AiPhase,
+    pub input_tokens: u32,
+    /// Reported by the provider once the response ends, estimated from the text before that.
+    pub output_tokens: u32,
+    pub elapsed: Duration,
+    pub estimated_cost: f64,
+    pub changes_seen: usize,
+}
+
+impl StreamProgress {
+    pub fn summary(&self) -> String {
+        let seconds = self.elapsed.as_secs();
+        let mut summary = format!(
+            "{} in / ~{} out tokens · {}m{:02}s · ${:.4}",
+            self.input_tokens, self.output_tokens, seconds / 60, seconds % 60, self.estimated_cost
+        );
+        if self.phase == AiPhase::Analysis {
+            summary.push_str(&format!(" · {} changes", self.changes_seen));
+        }
+        summary
+    }
+}
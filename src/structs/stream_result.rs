@@ -1,8 +1,28 @@
 use serde::{Deserialize, Serialize};
+use crate::structs::ai::tool_call::ToolCall;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct StreamResult {
     pub content: String,
     pub input_tokens: u32,
     pub output_tokens: u32,
+    pub stop_reason: Option<String>,
+    #[serde(default)]
+    pub thinking: String,
+    #[serde(default)]
+    pub cache_creation_tokens: u32,
+    #[serde(default)]
+    pub cache_read_tokens: u32,
+    #[serde(default)]
+    pub tool_calls: Vec<ToolCall>,
+    /// The stream was aborted by the user; everything above is partial.
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+impl StreamResult {
+    /// Anthropic reports `max_tokens`, OpenAI-compatible servers and Ollama report `length`.
+    pub fn is_truncated(&self) -> bool {
+        matches!(self.stop_reason.as_deref(), Some("max_tokens") | Some("length"))
+    }
 }
\ No newline at end of file
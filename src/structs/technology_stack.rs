@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
 pub struct TechnologyStack {
     pub primary_language: Option<String>,
     pub framework: Option<String>,
@@ -21,4 +22,48 @@ pub struct TechnologyStack {
     pub critical_configs: HashMap<String, String>,
 }
 
+impl TechnologyStack {
+    /// Folds in a stack detected from another part of the repository. Values that differ
+    /// are listed together; entries already known keep their first value.
+    pub fn merge(&mut self, other: TechnologyStack) {
+        Self::merge_value(&mut self.primary_language, other.primary_language);
+        Self::merge_value(&mut self.framework, other.framework);
+        Self::merge_value(&mut self.runtime, other.runtime);
+        Self::merge_value(&mut self.package_manager, other.package_manager);
+        Self::merge_value(&mut self.database, other.database);
+        Self::merge_value(&mut self.orm, other.orm);
+        Self::merge_value(&mut self.testing, other.testing);
+        Self::merge_value(&mut self.build_tools, other.build_tools);
+        Self::merge_value(&mut self.linting, other.linting);
+        Self::merge_value(&mut self.containerization, other.containerization);
+        Self::merge_value(&mut self.cloud_services, other.cloud_services);
+        Self::merge_value(&mut self.authentication, other.authentication);
+        Self::merge_value(&mut self.api_type, other.api_type);
+        Self::merge_value(&mut self.architecture_pattern, other.architecture_pattern);
 
+        for (name, version) in other.dependencies {
+            self.dependencies.entry(name).or_insert(version);
+        }
+        for (file, purpose) in other.critical_configs {
+            self.critical_configs.entry(file).or_insert(purpose);
+        }
+    }
+
+    fn merge_value(value: &mut Option<String>, other: Option<String>) {
+        let Some(other) = other.map(|o| o.trim().to_string()).filter(|o| !o.is_empty()) else {
+            return;
+        };
+
+        match value {
+            Some(current) => {
+                let known = current.split(", ").any(|part| part.eq_ignore_ascii_case(&other))
+                    || current.eq_ignore_ascii_case(&other);
+                if !known {
+                    current.push_str(", ");
+                    current.push_str(&other);
+                }
+            }
+            None => *value = Some(other),
+        }
+    }
+}
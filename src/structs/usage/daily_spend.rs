@@ -0,0 +1,57 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::errors::{AicedError, AicedResult};
+
+/// Running total of provider spend for one UTC day, persisted across runs. The ledger file is
+/// shared by concurrent runs, so it is always re-read under a file lock.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DailySpend {
+    pub day: u64,
+    pub cost: f64,
+}
+
+impl DailySpend {
+    pub fn load_for_day(path: &Path, day: u64) -> Self {
+        let stored = File::open(path).ok().and_then(|mut file| {
+            file.lock_shared().ok()?;
+            Self::read_from(&mut file)
+        });
+
+        match stored {
+            Some(spend) if spend.day == day => spend,
+            _ => Self { day, cost: 0.0 },
+        }
+    }
+
+    /// Adds `cost` to the spend of `day` in the ledger and returns the new total. The lock is
+    /// held from the read to the write, so spend recorded by other runs in between is kept.
+    pub fn add(path: &Path, day: u64, cost: f64) -> AicedResult<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        file.lock()?;
+
+        let mut spend = match Self::read_from(&mut file) {
+            Some(spend) if spend.day == day => spend,
+            _ => Self { day, cost: 0.0 },
+        };
+        spend.cost += cost;
+
+        let content = toml::to_string_pretty(&spend)
+            .map_err(|e| AicedError::system_error("serialize_daily_spend", &e.to_string()))?;
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(content.as_bytes())?;
+        Ok(spend)
+    }
+
+    fn read_from(file: &mut File) -> Option<Self> {
+        let mut content = String::new();
+        file.read_to_string(&mut content).ok()?;
+        toml::from_str(&content).ok()
+    }
+}
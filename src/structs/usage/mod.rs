@@ -0,0 +1,4 @@
+pub mod token_usage;
+pub mod usage_entry;
+pub mod daily_spend;
+pub mod run_record;
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::errors::AicedResult;
+use crate::structs::analysis_response::AnalysisResponse;
+use crate::structs::usage::usage_entry::UsageEntry;
+
+/// What a single `aiced analyze` invocation did, stored under `~/aiced/runs`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RunRecord {
+    pub id: String,
+    pub started_at: u64,
+    pub finished_at: u64,
+    pub repositories: Vec<String>,
+    pub usage: Vec<UsageEntry>,
+    pub total_cost: f64,
+    /// Extended-thinking output of the analysis, per repository.
+    #[serde(default)]
+    pub thinking: HashMap<String, String>,
+    /// Commit analyzed per repository, the base for `since = "last-run"`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub commits: HashMap<String, String>,
+    #[serde(default)]
+    pub cancelled: bool,
+    /// Analyses that were never reviewed because the run was cancelled, per repository.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub cancelled_analyses: HashMap<String, AnalysisResponse>,
+}
+
+impl RunRecord {
+    pub fn save_to_dir(&self, runs_dir: &Path) -> AicedResult<()> {
+        std::fs::create_dir_all(runs_dir)?;
+        let path = runs_dir.join(format!("{}-{}.json", self.started_at, self.id));
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        log::info!("💾 Run saved to {}", path.display());
+        Ok(())
+    }
+
+    /// The commit of `repository` in the latest run that analyzed it and was not cancelled.
+    pub fn last_commit_for(runs_dir: &Path, repository: &str) -> Option<String> {
+        std::fs::read_dir(runs_dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+            .filter_map(|content| serde_json::from_str::<RunRecord>(&content).ok())
+            .filter(|record| !record.cancelled)
+            .filter_map(|record| record.commits.get(repository).map(|commit| (record.started_at, commit.clone())))
+            .max_by_key(|(started_at, _)| *started_at)
+            .map(|(_, commit)| commit)
+    }
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use crate::enums::ai_phase::AiPhase;
+use crate::enums::ai_provider_kind::AiProviderKind;
+use crate::structs::usage::token_usage::TokenUsage;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UsageEntry {
+    pub repository: String,
+    pub phase: AiPhase,
+    /// Provider that served the requests; absent in runs recorded before fallbacks existed.
+    #[serde(default)]
+    pub provider: Option<AiProviderKind>,
+    pub model: String,
+    pub usage: TokenUsage,
+}
@@ -2,11 +2,43 @@ use async_trait::async_trait;
 use futures::Stream;
 use std::pin::Pin;
 use crate::enums::ai_provider_error::AiProviderError;
+use crate::enums::ai_provider_kind::AiProviderKind;
+use crate::helpers::token_estimator;
+use crate::structs::ai::chat_message::ChatMessage;
+use crate::structs::ai::tool_definition::ToolDefinition;
 use crate::structs::stream_item::StreamItem;
 
 #[async_trait]
 pub trait AiProvider: Send + Sync {
 
-    async fn stream_chat(&self, system_prompt: String, user_prompts: Vec<String>)
+    fn model(&self) -> &str;
+
+    /// The provider that serves `model`, recorded with the usage of every request.
+    fn kind(&self) -> AiProviderKind;
+
+    /// Whether responses from this provider cost money and count towards the budget.
+    fn is_billable(&self) -> bool {
+        true
+    }
+
+    /// Whether `stream_chat_with_tools` is implemented.
+    fn supports_tools(&self) -> bool {
+        false
+    }
+
+    async fn stream_chat(&self, messages: Vec<ChatMessage>)
                          -> Result<Pin<Box<dyn Stream<Item = Result<StreamItem, AiProviderError>> + Send>>, AiProviderError>;
-}
\ No newline at end of file
+
+    /// Streams a response in which the model may call the given tools; completed calls
+    /// arrive as `StreamItem::tool_call` items.
+    async fn stream_chat_with_tools(&self, _messages: Vec<ChatMessage>, _tools: Vec<ToolDefinition>)
+                                    -> Result<Pin<Box<dyn Stream<Item = Result<StreamItem, AiProviderError>> + Send>>, AiProviderError> {
+        Err(AiProviderError::ApiError(format!("Model '{}' is not served by a provider with tool support", self.model())))
+    }
+
+    /// Counts the input tokens of a request without sending it. Providers without a
+    /// counting endpoint fall back to the local estimator.
+    async fn count_tokens(&self, messages: Vec<ChatMessage>) -> Result<u32, AiProviderError> {
+        Ok(token_estimator::estimate_message_tokens(&messages))
+    }
+}
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use tokio::sync::oneshot;
 use tokio::time::timeout;
 use warp::Filter;
+use tokio_util::sync::CancellationToken;
 use serde_json::json;
 use crate::config::constants::{
     DEFAULT_SERVER_PORT_RANGE_START, DEFAULT_SERVER_PORT_RANGE_END, 
@@ -77,14 +78,14 @@ impl DiffServer {
         Ok(port)
     }
 
-    pub async fn create_session(&self, repository_config: &RepositoryConfig, changes: Vec<FileChange>) -> AicedResult<String> {
-        self.session_manager.create_session(repository_config, &changes)
+    pub async fn create_session(&self, repository_config: &RepositoryConfig, changes: Vec<FileChange>, thinking: &str) -> AicedResult<String> {
+        self.session_manager.create_session(repository_config, &changes, thinking)
     }
 
-    pub async fn wait_for_completion(&self, session_id: &str, timeout_minutes: u64) -> AicedResult<Vec<String>> {
+    pub async fn wait_for_completion(&self, session_id: &str, timeout_minutes: u64, cancellation: &CancellationToken) -> AicedResult<Vec<String>> {
         let timeout_dur = timeout_duration(timeout_minutes);
 
-        let result = timeout(timeout_dur, async {
+        let review = timeout(timeout_dur, async {
             loop {
                 if let Some(session) = self.session_manager.get_session(session_id) {
                     match session.status {
@@ -107,7 +108,12 @@ impl DiffServer {
                     ));
                 }
             }
-        }).await;
+        });
+
+        let result = tokio::select! {
+            result = review => result,
+            _ = cancellation.cancelled() => return Err(AicedError::cancelled("diff review")),
+        };
 
         match result {
             Ok(applied_changes) => applied_changes,
@@ -23,19 +23,19 @@ impl SessionManager {
         }
     }
 
-    pub fn create_session(&self, repository_config: &RepositoryConfig, changes: &[FileChange]) -> AicedResult<String> {
+    pub fn create_session(&self, repository_config: &RepositoryConfig, changes: &[FileChange], thinking: &str) -> AicedResult<String> {
         let session_id = Uuid::new_v4().to_string();
 
         let mut files_map: HashMap<String, FileDiff> = HashMap::new();
         
         for change in changes {
             match change {
-                FileChange::ModifyFile { file_path, reason, line_changes, .. } => {
+                FileChange::ModifyFile { file_path, reason, line_changes, rationale, .. } => {
                     if files_map.contains_key(file_path) {
                         if let Some(file_diff) = files_map.get_mut(file_path) {
                             // Add the new change items
                             for line_change in line_changes {
-                                let change_item = self.line_change_to_change_item(line_change, reason)?;
+                                let change_item = self.line_change_to_change_item(line_change, reason, rationale.as_deref())?;
                                 file_diff.changes.push(change_item);
                             }
                             
@@ -136,20 +136,22 @@ impl SessionManager {
                             repository_config,
                             file_path,
                             reason,
+                            rationale.as_deref(),
                             line_changes,
                         )?;
                         files_map.insert(file_path.to_string(), diff);
                     }
                 }
-                FileChange::CreateFile { file_path, reason, content, .. } => {
-                    let diff = self.create_new_file_diff(file_path, reason, content)?;
+                FileChange::CreateFile { file_path, reason, content, rationale, .. } => {
+                    let diff = self.create_new_file_diff(file_path, reason, rationale.as_deref(), content)?;
                     files_map.insert(file_path.to_string(), diff);
                 }
-                FileChange::DeleteFile { file_path, reason, .. } => {
+                FileChange::DeleteFile { file_path, reason, rationale, .. } => {
                     let diff = self.create_delete_file_diff(
                         repository_config,
                         file_path,
                         reason,
+                        rationale.as_deref(),
                     )?;
                     files_map.insert(file_path.to_string(), diff);
                 }
@@ -161,6 +163,7 @@ impl SessionManager {
             repository_name: repository_config.name.clone(),
             repository_path: repository_config.path.clone(),
             files: files_map.into_iter().map(|(_, file_diff)| file_diff).collect(),
+            thinking: thinking.to_string(),
             applied_changes: HashSet::new(),
             status: SessionStatus::Active,
         };
@@ -243,14 +246,14 @@ impl SessionManager {
         }
     }
 
-    fn create_file_diff(&self, repository_config: &RepositoryConfig, file_path: &str, reason: &str, line_changes: &[LineChange]) -> AicedResult<FileDiff> {
+    fn create_file_diff(&self, repository_config: &RepositoryConfig, file_path: &str, reason: &str, rationale: Option<&str>, line_changes: &[LineChange]) -> AicedResult<FileDiff> {
         let full_path = format!("{}/{}", repository_config.path, file_path).replace("//", "/");
         let original_content = std::fs::read_to_string(&full_path)?;
 
         let mut changes = Vec::new();
 
         for line_change in line_changes {
-            let change_item = self.line_change_to_change_item(line_change, reason)?;
+            let change_item = self.line_change_to_change_item(line_change, reason, rationale)?;
             changes.push(change_item);
         }
 
@@ -267,7 +270,7 @@ impl SessionManager {
         })
     }
 
-    fn create_new_file_diff(&self, file_path: &str, reason: &str, content: &str) -> AicedResult<FileDiff> {
+    fn create_new_file_diff(&self, file_path: &str, reason: &str, rationale: Option<&str>, content: &str) -> AicedResult<FileDiff> {
         let change_item = ChangeItem {
             id: Uuid::new_v4().to_string(),
             change_type: "create_file".to_string(),
@@ -276,6 +279,7 @@ impl SessionManager {
             new_content: Some(content.to_string()),
             applied: false,
             reason: reason.to_string(),
+            rationale: rationale.map(|r| r.to_string()),
         };
 
         let file_type = self.detect_file_type(file_path);
@@ -289,7 +293,7 @@ impl SessionManager {
         })
     }
 
-    fn create_delete_file_diff(&self, repository_config: &RepositoryConfig, file_path: &str, reason: &str) -> AicedResult<FileDiff> {
+    fn create_delete_file_diff(&self, repository_config: &RepositoryConfig, file_path: &str, reason: &str, rationale: Option<&str>) -> AicedResult<FileDiff> {
         let full_path = format!("{}/{}", repository_config.path, file_path).replace("//", "/");
         let original_content = std::fs::read_to_string(&full_path).unwrap_or_default();
 
@@ -301,6 +305,7 @@ impl SessionManager {
             new_content: None,
             applied: false,
             reason: reason.to_string(),
+            rationale: rationale.map(|r| r.to_string()),
         };
 
         let file_type = self.detect_file_type(file_path);
@@ -314,7 +319,7 @@ impl SessionManager {
         })
     }
 
-    fn line_change_to_change_item(&self, line_change: &LineChange, reason: &str) -> AicedResult<ChangeItem> {
+    fn line_change_to_change_item(&self, line_change: &LineChange, reason: &str, rationale: Option<&str>) -> AicedResult<ChangeItem> {
         let (change_type, line_number, old_content, new_content) = match line_change {
             LineChange::Replace { line_number, old_content, new_content } => {
                 ("replace".to_string(), *line_number, Some(old_content.clone()), Some(new_content.clone()))
@@ -350,6 +355,7 @@ impl SessionManager {
             new_content,
             applied: false,
             reason: reason.to_string(),
+            rationale: rationale.map(|r| r.to_string()),
         })
     }
 
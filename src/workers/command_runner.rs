@@ -1,15 +1,23 @@
+use std::collections::HashSet;
+use std::path::Path;
 use std::rc::Rc;
 use std::sync::Arc;
 use std::io::{self, Write};
 use std::time::{Instant};
-use crate::config::constants::DEFAULT_TIMEOUT_MINUTES;
+use tokio_util::sync::CancellationToken;
+use crate::config::constants::{DEFAULT_TIMEOUT_MINUTES, EXIT_CODE_CANCELLED};
 use crate::enums::commands::Commands;
+use crate::enums::prompt_commands::PromptCommands;
 use crate::config::config_manager::ConfigManager;
 use crate::errors::{AicedError, AicedResult};
 use crate::services::file_modifier::FileModifier;
+use crate::services::repo_scanner::RepoScanner;
 use crate::services::repository_manager::RepositoryManager;
+use crate::services::usage_tracker::UsageTracker;
 use crate::structs::analyze_repository_response::AnalyzeRepositoryResponse;
 use crate::structs::config::config::Config;
+use crate::structs::file_rules::FileRules;
+use crate::structs::ignore_matcher::IgnoreMatcher;
 use crate::ui::diff_server::DiffServer;
 
 pub struct CommandRunner {
@@ -28,11 +36,13 @@ impl CommandRunner {
 
         let result = match command {
             Commands::Init => self.init_command().await,
-            Commands::Analyze { repo, tags, profile } => self.analyze_command(repo, tags, profile).await,
+            Commands::Analyze { repo, tags, profile, since, categories } => self.analyze_command(repo, tags, profile, since, categories).await,
             Commands::List => self.list_command().await,
             Commands::Dashboard { port } => self.dashboard_command(port).await,
             Commands::Validate => self.validate_command().await,
             Commands::History { repo, days } => self.history_command(repo, days).await,
+            Commands::Scan { repo, explain } => self.scan_command(repo, explain).await,
+            Commands::Prompt { action: PromptCommands::Render { repo, profile, categories, system_only } } => self.prompt_render_command(repo, profile, categories, system_only).await,
         };
 
         if let Some(start) = self.start_time {
@@ -58,9 +68,65 @@ impl CommandRunner {
         Ok(())
     }
 
-    async fn analyze_command(&self, repo: Option<String>, _tags: Vec<String>, _profile: Option<String>) -> AicedResult<()> {
+    async fn analyze_command(&self, repo: Option<String>, _tags: Vec<String>, profile: Option<String>, since: Option<String>, categories: Vec<String>) -> AicedResult<()> {
         log::info!("🔍 Starting code analysis...");
 
+        let config = self.load_config(profile.as_deref(), categories)?;
+
+        let usage_tracker = Arc::new(UsageTracker::new(&config));
+        let cancellation = CancellationToken::new();
+        let ctrl_c_handler = Self::spawn_ctrl_c_handler(cancellation.clone());
+        let mut results: Vec<Rc<AnalyzeRepositoryResponse>> = Vec::new();
+        let mut manager = RepositoryManager::new(Rc::clone(&config), Arc::clone(&usage_tracker), cancellation.clone(), since);
+
+        let analysis_result = if let Some(repo_name) = repo {
+            self.analyze_single_repository(&mut manager, &repo_name, &mut results).await
+        } else {
+            self.analyze_all_repositories(&mut manager, &mut results).await
+        };
+
+        if analysis_result.is_ok() && results.is_empty() {
+            log::info!("⚠️ No repositories were successfully analyzed.");
+            log::info!("💡 Check the errors above and verify your configuration.");
+        }
+
+        if !results.is_empty() {
+            log::info!("✅ Analysis complete for {} repositories", results.len());
+        }
+
+        // The spend is shown before the review, which can take a while.
+        usage_tracker.log_summary();
+
+        let mut reviewed = HashSet::new();
+        for result in &results {
+            if cancellation.is_cancelled() {
+                break;
+            }
+            match self.process_repository_result_enhanced(Rc::clone(result), &config, &cancellation).await {
+                Ok(()) => {
+                    reviewed.insert(result.repository_config.name.clone());
+                }
+                Err(e) if e.is_cancelled() => break,
+                Err(e) => {
+                    log::error!("❌ Error processing repository results: {}", e);
+                    log::error!("   Continuing with next repository...");
+                }
+            }
+        }
+
+        ctrl_c_handler.abort();
+        let cancelled = cancellation.is_cancelled();
+        self.finish_run(&usage_tracker, &results, &reviewed, cancelled);
+
+        if cancelled {
+            return Err(AicedError::cancelled("analyze"));
+        }
+        analysis_result
+    }
+
+    /// Loads and validates the configuration, with the named profile and the categories
+    /// from the command line applied.
+    fn load_config(&self, profile: Option<&str>, categories: Vec<String>) -> AicedResult<Rc<Config>> {
         let config = match ConfigManager::load() {
             Ok(config) => config,
             Err(e) => {
@@ -70,35 +136,141 @@ impl CommandRunner {
             }
         };
 
+        let config = if profile.is_some() || !categories.is_empty() {
+            let mut config = (*config).clone();
+            if let Some(name) = profile {
+                config.apply_profile(name)?;
+            }
+            if !categories.is_empty() {
+                config.override_categories(categories);
+            }
+            Rc::new(config)
+        } else {
+            config
+        };
         ConfigManager::validate_config(Rc::clone(&config))?;
+        Ok(config)
+    }
 
-        let mut results: Vec<Rc<AnalyzeRepositoryResponse>> = Vec::new();
-        let mut manager = RepositoryManager::new(Rc::clone(&config));
+    /// Prints the system prompt and the user prompt of every batch exactly as the analysis
+    /// would send them. No AI provider is built, and a stale file filter cache is replaced
+    /// by the selection rules.
+    async fn prompt_render_command(&self, repo: String, profile: Option<String>, categories: Vec<String>, system_only: bool) -> AicedResult<()> {
+        let config = self.load_config(profile.as_deref(), categories)?;
+        let repo_config = config.repositories
+            .iter()
+            .find(|r| r.name == repo)
+            .cloned()
+            .ok_or_else(|| AicedError::repo_error(&repo, "render prompt", "Repository not found"))?;
 
-        if let Some(repo_name) = repo {
-            self.analyze_single_repository(&mut manager, &repo_name, &mut results).await?;
-        } else {
-            self.analyze_all_repositories(&mut manager, &mut results).await?;
-        }
+        let usage_tracker = Arc::new(UsageTracker::new(&config));
+        let manager = RepositoryManager::new(Rc::clone(&config), usage_tracker, CancellationToken::new(), None);
+        let planner = manager.create_planner(Arc::new(repo_config))?;
 
-        if results.is_empty() {
-            log::info!("⚠️ No repositories were successfully analyzed.");
-            log::info!("💡 Check the errors above and verify your configuration.");
+        println!("===== SYSTEM PROMPT =====\n{}\n", planner.system_prompt());
+        if system_only {
             return Ok(());
         }
 
-        log::info!("✅ Analysis complete for {} repositories", results.len());
+        let user_prompts = planner.render_user_prompts().await?;
+        if user_prompts.is_empty() {
+            log::info!("✨ No changed files to analyze in {}", repo);
+        }
+        let total = user_prompts.len();
+        for (index, prompt) in user_prompts.iter().enumerate() {
+            println!("===== USER PROMPT ({}/{}) =====\n{}\n", index + 1, total, prompt);
+        }
+        Ok(())
+    }
+
+    /// Lists the files that pass the ignore rules, or prints the rule that decides about the
+    /// `explain` path. Explaining reads only the ignore files and needs no provider.
+    async fn scan_command(&self, repo: String, explain: Option<String>) -> AicedResult<()> {
+        let config = self.load_config(None, Vec::new())?;
+        let repo_config = config.repositories
+            .iter()
+            .find(|r| r.name == repo)
+            .cloned()
+            .ok_or_else(|| AicedError::repo_error(&repo, "scan", "Repository not found"))?;
+
+        let Some(path) = explain else {
+            let analysis_config = config.analysis_config_for(&repo_config);
+            let scanner = RepoScanner::new(Arc::new(repo_config.clone()))
+                .with_limits(analysis_config.max_file_size_bytes(), analysis_config.max_files)
+                .with_file_selection(analysis_config.file_selection, FileRules::from_config(&analysis_config));
+            for path in scanner.candidate_files().await? {
+                println!("{}", path.strip_prefix(&repo_config.path).unwrap_or(&path).display());
+            }
+            return Ok(());
+        };
 
-        for result in results {
-            if let Err(e) = self.process_repository_result_enhanced(result, &config).await {
-                log::error!("❌ Error processing repository results: {}", e);
-                log::error!("   Continuing with next repository...");
+        let repo_path = Path::new(&repo_config.path);
+        let relative = match Path::new(&path).strip_prefix(repo_path) {
+            Ok(relative) => relative.to_string_lossy().to_string(),
+            Err(_) if Path::new(&path).is_absolute() => {
+                return Err(AicedError::repo_error(&repo, "explain path", &format!("'{}' is outside {}", path, repo_config.path)));
             }
-        }
+            Err(_) => path.trim_start_matches("./").to_string(),
+        };
+        let relative = relative.trim_matches('/');
+        let is_dir = path.ends_with('/') || repo_path.join(relative).is_dir();
 
+        let mut matcher = IgnoreMatcher::for_repo(&repo_config.path);
+        match matcher.explain(relative, is_dir) {
+            Some((_, rule)) if rule.is_negated() => {
+                println!("{}: scanned, re-included by {} ({})", relative, rule.source, rule.pattern);
+            }
+            Some((matched, rule)) if matched != relative => {
+                println!("{}: ignored, its directory {} is ignored by {} ({})", relative, matched, rule.source, rule.pattern);
+            }
+            Some((_, rule)) => println!("{}: ignored by {} ({})", relative, rule.source, rule.pattern),
+            None => println!("{}: scanned, no ignore rule matches", relative),
+        }
         Ok(())
     }
 
+    /// The first Ctrl+C cancels the run cooperatively, a second one exits right away.
+    fn spawn_ctrl_c_handler(cancellation: CancellationToken) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_err() {
+                return;
+            }
+            log::warn!("🛑 Ctrl+C received, cancelling... (press Ctrl+C again to exit immediately)");
+            cancellation.cancel();
+
+            if tokio::signal::ctrl_c().await.is_ok() {
+                log::warn!("🛑 Exiting immediately");
+                std::process::exit(EXIT_CODE_CANCELLED);
+            }
+        })
+    }
+
+    /// `reviewed` names the repositories whose results went through review successfully
+    /// before the run ended.
+    fn finish_run(&self, usage_tracker: &UsageTracker, results: &[Rc<AnalyzeRepositoryResponse>], reviewed: &HashSet<String>, cancelled: bool) {
+        let repositories = results.iter().map(|r| r.repository_config.name.clone()).collect();
+        let thinking = results
+            .iter()
+            .filter(|r| !r.repository_analysis.thinking.is_empty())
+            .map(|r| (r.repository_config.name.clone(), r.repository_analysis.thinking.clone()))
+            .collect();
+        // Only repositories that made it through review count as analyzed for "last-run".
+        let commits = results
+            .iter()
+            .filter(|r| reviewed.contains(&r.repository_config.name))
+            .filter_map(|r| r.commit.clone().map(|commit| (r.repository_config.name.clone(), commit)))
+            .collect();
+        // The analyses a cancelled run did not get to review are kept for later.
+        let cancelled_analyses = cancelled.then(|| results
+            .iter()
+            .filter(|r| !reviewed.contains(&r.repository_config.name))
+            .map(|r| (r.repository_config.name.clone(), (*r.repository_analysis).clone()))
+            .collect());
+        if let Err(e) = usage_tracker.save_run(repositories, thinking, commits, cancelled_analyses) {
+            log::error!("❌ Failed to save run record: {}", e);
+        }
+    }
+
     async fn analyze_single_repository(&self, manager: &mut RepositoryManager, repo_name: &str, results: &mut Vec<Rc<AnalyzeRepositoryResponse>>) -> AicedResult<()> {
         log::info!("🎯 Analyzing repository: {}", repo_name);
         let repo_config = manager.config.repositories
@@ -111,6 +283,7 @@ impl CommandRunner {
             Ok(_) => {
                 log::info!("✅ Successfully analyzed repository: {}", repo_config.name);
             }
+            Err(e) if e.is_cancelled() => return Err(e),
             Err(e) => {
                 log::error!("❌ Failed to analyze repository '{}': {}", repo_config.name, e);
                 return Err(e);
@@ -127,6 +300,7 @@ impl CommandRunner {
             Ok(_) => {
                 log::info!("✅ Successfully analyzed all repositories");
             }
+            Err(e) if e.is_cancelled() => return Err(e),
             Err(e) => {
                 log::error!("❌ Error during repository analysis: {}", e);
                 log::error!("   Some repositories may have failed. Continuing with successful ones...");
@@ -136,9 +310,14 @@ impl CommandRunner {
         Ok(())
     }
 
-    async fn process_repository_result_enhanced(&self, result: Rc<AnalyzeRepositoryResponse>, config: &Config) -> AicedResult<()> {
+    async fn process_repository_result_enhanced(&self, result: Rc<AnalyzeRepositoryResponse>, config: &Config, cancellation: &CancellationToken) -> AicedResult<()> {
         log::info!("📊 Processing results for: {}", result.repository_config.name);
 
+        if result.repository_analysis.changes.is_empty() {
+            log::info!("✨ No changes proposed for {}, nothing to review", result.repository_config.name);
+            return Ok(());
+        }
+
         let validation_result = FileModifier::validate_changes_batch(
             &result.repository_config,
             &result.repository_analysis.changes
@@ -148,7 +327,7 @@ impl CommandRunner {
             log::error!("❌ Validation failed. Skipping this repository.");
             return Ok(());
         }
-        self.apply_changes_individually(&result).await?;
+        self.apply_changes_individually(&result, cancellation).await?;
         self.handle_post_application_workflow(result, config).await?;
         Ok(())
     }
@@ -323,7 +502,7 @@ impl CommandRunner {
         Ok(())
     }
 
-    async fn apply_changes_individually(&self, result: &AnalyzeRepositoryResponse) -> AicedResult<bool> {
+    async fn apply_changes_individually(&self, result: &AnalyzeRepositoryResponse, cancellation: &CancellationToken) -> AicedResult<bool> {
         log::info!("🌐 Starting interactive diff viewer...");
 
         let mut diff_server = DiffServer::new();
@@ -331,7 +510,8 @@ impl CommandRunner {
 
         let session_id = diff_server.create_session(
             &result.repository_config,
-            result.repository_analysis.changes.clone()
+            result.repository_analysis.changes.clone(),
+            &result.repository_analysis.thinking,
         ).await?;
 
         let url = format!("http://localhost:{}?session={}", port, session_id);
@@ -352,9 +532,10 @@ impl CommandRunner {
         log::info!("👆 Review changes in your browser and click 'Complete Review' when done");
         log::info!("⏱️ Waiting for review completion (timeout: {} minutes)...", DEFAULT_TIMEOUT_MINUTES);
 
-        let applied_change_ids = diff_server.wait_for_completion(&session_id, DEFAULT_TIMEOUT_MINUTES).await?;
+        let applied_change_ids = diff_server.wait_for_completion(&session_id, DEFAULT_TIMEOUT_MINUTES, cancellation).await;
 
         diff_server.shutdown().await?;
+        let applied_change_ids = applied_change_ids?;
 
         if applied_change_ids.is_empty() {
             log::info!("📊 No changes approved for application");
@@ -0,0 +1,72 @@
+mod common;
+
+use common::{log_of, run_aiced, setup_home, write_config, MockServer, ANALYSIS_WITHOUT_CHANGES};
+
+/// An Anthropic Messages stream that answers with `text`.
+fn anthropic_sse(text: &str) -> String {
+    let events = [
+        serde_json::json!({"type": "message_start", "message": {"usage": {"input_tokens": 120, "output_tokens": 1}}}),
+        serde_json::json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+        serde_json::json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": text}}),
+        serde_json::json!({"type": "content_block_stop", "index": 0}),
+        serde_json::json!({"type": "message_delta", "delta": {"stop_reason": "end_turn"}, "usage": {"output_tokens": 40}}),
+        serde_json::json!({"type": "message_stop"}),
+    ];
+    events
+        .iter()
+        .map(|event| format!("event: {}\ndata: {}\n\n", event["type"].as_str().unwrap(), event))
+        .collect()
+}
+
+fn config(base_url: &str, mode: &str, cassettes: &std::path::Path) -> String {
+    format!(
+        "[ai]\nprovider = \"anthropic\"\nbase_url = \"{}\"\nthinking = false\nmax_retries = 0\n\n\
+         [analysis]\nfile_selection = \"rules\"\nchunk_strategy = \"single\"\n\n\
+         [cassette]\nmode = \"{}\"\ndir = \"{}\"\n",
+        base_url, mode, cassettes.display()
+    )
+}
+
+#[test]
+fn replays_recorded_raw_streams_without_network_or_api_key() {
+    let server = MockServer::start(|request| match request.path.ends_with("/count_tokens") {
+        true => ("application/json", r#"{"input_tokens": 120}"#.to_string()),
+        false => ("text/event-stream", anthropic_sse(ANALYSIS_WITHOUT_CHANGES)),
+    });
+    let cassettes = tempfile::tempdir().unwrap();
+    let home = setup_home(&config(&server.base_url("/v1"), "record", cassettes.path()));
+
+    let recorded = run_aiced(home.path(), &["analyze", "--repo", "demo"], &[("ANTHROPIC_API_KEY", "test-key")]);
+    assert!(recorded.status.success(), "{}", log_of(&recorded));
+    let served = server.requests().len();
+    assert!(server.requests().iter().any(|request| request.body.contains("src/main.rs")));
+
+    // The cassettes hold the provider's bytes as sent, not parsed stream items.
+    let streams: Vec<String> = std::fs::read_dir(cassettes.path())
+        .unwrap()
+        .map(|entry| std::fs::read_to_string(entry.unwrap().path()).unwrap())
+        .collect();
+    assert!(streams.iter().any(|stream| stream.contains("event: message_start\ndata: ")));
+
+    // Nothing listens on the new base URL, so any request would fail the run.
+    let closed = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    write_config(home.path(), &config(&format!("http://127.0.0.1:{}/v1", closed), "replay", cassettes.path()));
+
+    let replayed = run_aiced(home.path(), &["analyze", "--repo", "demo"], &[]);
+    let log = log_of(&replayed);
+    assert!(replayed.status.success(), "{}", log);
+    assert!(log.contains("Replaying cassette"), "{}", log);
+    assert!(log.contains("No changes proposed for demo"), "{}", log);
+    assert_eq!(server.requests().len(), served);
+}
+
+#[test]
+fn replay_without_a_recording_fails() {
+    let cassettes = tempfile::tempdir().unwrap();
+    let home = setup_home(&config("http://127.0.0.1:9/v1", "replay", cassettes.path()));
+
+    let output = run_aiced(home.path(), &["analyze", "--repo", "demo"], &[]);
+    let log = log_of(&output);
+    assert!(!output.status.success(), "{}", log);
+    assert!(log.contains("No cassette recorded for this request"), "{}", log);
+}
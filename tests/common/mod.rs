@@ -0,0 +1,127 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::{Command, Output};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A request received by the `MockServer`.
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub path: String,
+    pub body: String,
+}
+
+/// A minimal HTTP server on a free local port that answers every request with the
+/// content type and body returned by its handler.
+pub struct MockServer {
+    pub port: u16,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockServer {
+    pub fn start<F>(respond: F) -> Self
+    where
+        F: Fn(&MockRequest) -> (&'static str, String) + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind mock server");
+        let port = listener.local_addr().unwrap().port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let received = Arc::clone(&requests);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if let Some(request) = read_request(&stream) {
+                    let (content_type, body) = respond(&request);
+                    received.lock().unwrap().push(request);
+                    write_response(stream, content_type, &body);
+                }
+            }
+        });
+
+        Self { port, requests }
+    }
+
+    pub fn base_url(&self, path: &str) -> String {
+        format!("http://127.0.0.1:{}{}", self.port, path)
+    }
+
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn read_request(stream: &TcpStream) -> Option<MockRequest> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let path = request_line.split_whitespace().nth(1)?.to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).ok()?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().ok()?;
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).ok()?;
+    Some(MockRequest { path, body: String::from_utf8_lossy(&body).to_string() })
+}
+
+fn write_response(mut stream: TcpStream, content_type: &str, body: &str) {
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        content_type, body.len(), body
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
+
+/// A home directory with `aiced/config.toml` and a repository named `demo` holding one
+/// Rust file. `config` is the configuration without the repository table.
+pub fn setup_home(config: &str) -> tempfile::TempDir {
+    let home = tempfile::tempdir().unwrap();
+    let repo = home.path().join("demo");
+    std::fs::create_dir_all(repo.join("src")).unwrap();
+    std::fs::write(repo.join("src/main.rs"), "fn main() {\n    println!(\"hello\");\n}\n").unwrap();
+    write_config(home.path(), config);
+    home
+}
+
+pub fn write_config(home: &Path, config: &str) {
+    let config = format!("{}\n\n[[repositories]]\nname = \"demo\"\npath = \"{}\"\n", config, home.join("demo").display());
+    std::fs::create_dir_all(home.join("aiced")).unwrap();
+    std::fs::write(home.join("aiced/config.toml"), config).unwrap();
+}
+
+/// Runs the binary with `home` as the home directory and without any API key in the
+/// environment unless given in `env`.
+pub fn run_aiced(home: &Path, args: &[&str], env: &[(&str, &str)]) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_aiced-cli"));
+    command
+        .args(args)
+        .env("HOME", home)
+        .env_remove("ANTHROPIC_API_KEY")
+        .env_remove("OPENAI_API_KEY")
+        .env_remove("RUST_LOG");
+    for (name, value) in env {
+        command.env(name, value);
+    }
+    command.output().expect("run aiced")
+}
+
+/// Everything the binary logged, for assertions and failure messages.
+pub fn log_of(output: &Output) -> String {
+    format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr))
+}
+
+/// The response of a text-protocol analysis that proposes no changes.
+pub const ANALYSIS_WITHOUT_CHANGES: &str = "TECHNOLOGY_STACK:\nPRIMARY_LANGUAGE: Rust\nDEPENDENCIES:\nEND_DEPENDENCIES\nEND_TECHNOLOGY_STACK\n\nANALYSIS_SUMMARY:\nThe code looks fine.\n";
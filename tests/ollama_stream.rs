@@ -0,0 +1,58 @@
+mod common;
+
+use common::{log_of, run_aiced, setup_home, MockServer, ANALYSIS_WITHOUT_CHANGES};
+
+fn config(base_url: &str) -> String {
+    format!(
+        "[ai]\nprovider = \"ollama\"\nmodel = \"qwen2.5-coder\"\nbase_url = \"{}\"\n\n\
+         [analysis]\nfile_selection = \"rules\"\nchunk_strategy = \"single\"\n",
+        base_url
+    )
+}
+
+/// The NDJSON lines of an `/api/chat` stream that sends `text` in a few pieces.
+fn content_lines(text: &str) -> Vec<String> {
+    let pieces = text.len().div_ceil(3);
+    text.as_bytes()
+        .chunks(pieces)
+        .map(|piece| serde_json::json!({"message": {"role": "assistant", "content": String::from_utf8_lossy(piece)}, "done": false}).to_string())
+        .collect()
+}
+
+#[test]
+fn reports_usage_from_the_done_line() {
+    let server = MockServer::start(|_| {
+        let mut lines = content_lines(ANALYSIS_WITHOUT_CHANGES);
+        lines.push(serde_json::json!({"done": true, "done_reason": "stop", "prompt_eval_count": 321, "eval_count": 45}).to_string());
+        ("application/x-ndjson", lines.join("\n") + "\n")
+    });
+    let home = setup_home(&config(&server.base_url("")));
+
+    let output = run_aiced(home.path(), &["analyze", "--repo", "demo"], &[]);
+    let log = log_of(&output);
+    assert!(output.status.success(), "{}", log);
+    assert!(log.contains("No changes proposed for demo"), "{}", log);
+    assert!(log.contains("1 requests, 321 input + 45 output tokens"), "{}", log);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/api/chat");
+    assert!(requests[0].body.contains(r#""stream":true"#));
+}
+
+#[test]
+fn fails_on_an_error_line_mid_stream() {
+    let server = MockServer::start(|_| {
+        let mut lines = content_lines(ANALYSIS_WITHOUT_CHANGES);
+        lines.truncate(1);
+        lines.push(serde_json::json!({"error": "model runner has unexpectedly stopped"}).to_string());
+        ("application/x-ndjson", lines.join("\n") + "\n")
+    });
+    let home = setup_home(&config(&server.base_url("")));
+
+    let output = run_aiced(home.path(), &["analyze", "--repo", "demo"], &[]);
+    let log = log_of(&output);
+    assert!(!output.status.success(), "{}", log);
+    assert!(log.contains("model runner has unexpectedly stopped"), "{}", log);
+    assert!(!log.contains("No changes proposed"), "{}", log);
+}